@@ -0,0 +1,43 @@
+use async_rust_benchmarks::{
+    _01::{
+        future::{FutureActor, Unconstrained},
+        select::RandomSelectActor,
+        spawn::{SpawnActor, SpawnJoinSetActor},
+    },
+    _02::{future::JoinSetFutureActor, select::JoinSetSelectActor},
+    harness::{Config, CountingAllocator, Registry, Report, Runner, RuntimeConfig},
+};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn main() {
+    let config = Config {
+        runtimes: vec![
+            RuntimeConfig::current_thread(),
+            RuntimeConfig::multi_thread(),
+        ],
+        ..Config::new("02")
+    }
+    .with_env_args();
+    println!("{config}");
+
+    let mut registry = Registry::new();
+    registry
+        .register(
+            "FutureActorUnconstrained",
+            FutureActor::<Unconstrained>::new,
+        )
+        .register(
+            "JoinSetFutureActorUnconstrained",
+            JoinSetFutureActor::<Unconstrained>::new,
+        )
+        .register("RandomSelectActor", RandomSelectActor::new)
+        .register("JoinSetSelectActor", JoinSetSelectActor::new)
+        .register("SpawnActor", SpawnActor::new)
+        .register("SpawnJoinSetActor", SpawnJoinSetActor::new);
+
+    let mut report = Report::new(&config);
+    Runner::new(&config).run(&registry, &mut report);
+    report.finish();
+}
@@ -0,0 +1,139 @@
+use tabled::{
+    Table,
+    settings::{Color, object::Rows},
+};
+
+use async_rust_benchmarks::{
+    _03::{
+        DynHandler, HandlerActor,
+        handler::{AsyncFnHandler, BoxedHandler, StaticHandler},
+    },
+    harness::{
+        Config, CountingAllocator, Registry, Report, Scenario, ScenarioResult, ToRow,
+        comparison_table,
+    },
+};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// The scenarios that can be selected. This benchmark doesn't measure latency.
+const SCENARIOS: [Scenario; 2] = [Scenario::Throughput, Scenario::Load];
+
+fn main() {
+    let config = Config {
+        scenarios: SCENARIOS.to_vec(),
+        ..Config::new("03")
+    }
+    .with_env_args();
+
+    if let Some(scenario) = config
+        .scenarios
+        .iter()
+        .find(|scenario| !SCENARIOS.contains(scenario))
+    {
+        eprintln!(
+            "error: scenario `{}` is not supported, only `throughput` and `load`",
+            scenario.name()
+        );
+        std::process::exit(2);
+    }
+    println!("{config}");
+
+    let mut registry = Registry::new();
+    registry
+        .register("StaticHandler", |rx, tx| {
+            HandlerActor::new(rx, StaticHandler, tx)
+        })
+        .register("AsyncFnHandler", |rx, tx| {
+            HandlerActor::new(rx, AsyncFnHandler, tx)
+        })
+        .register("BoxedHandler", |rx, tx| {
+            HandlerActor::new(rx, Box::new(BoxedHandler) as Box<dyn DynHandler>, tx)
+        });
+
+    let mut throughput = Vec::new();
+    let mut allocations = Vec::new();
+    let mut load = Vec::new();
+    let mut runtime_metrics = Vec::new();
+    let mut report = Report::new(&config);
+
+    for runtime in &config.runtimes {
+        let runtimes = runtime.build();
+        let (columns, name) = (runtime.columns(), runtime.to_string());
+
+        for actor in registry.actors() {
+            if config.is_selected(actor.name(), Scenario::Throughput) {
+                let result = actor.run(&runtimes, Scenario::Throughput, &config);
+                let ScenarioResult::Throughput(throughput_result) = &result else {
+                    unreachable!()
+                };
+                let allocation_row = throughput_result.allocations.to_row(actor.name());
+
+                throughput.push((
+                    columns.clone(),
+                    throughput_result.to_row(actor.name()),
+                    result.samples(),
+                ));
+                report.add_row(&name, actor.name(), "allocations", &allocation_row);
+                report.add(&name, actor.name(), result);
+                allocations.push((columns.clone(), allocation_row));
+            }
+
+            if config.is_selected(actor.name(), Scenario::Load) {
+                let result = actor.run(&runtimes, Scenario::Load, &config);
+                let ScenarioResult::Load(load_result) = &result else {
+                    unreachable!()
+                };
+                // Compared by poll duration rather than load, which is what this benchmark is
+                // about.
+                let poll_durations = load_result
+                    .steady_state()
+                    .iter()
+                    .map(|interval| interval.mean_poll_duration().as_nanos() as f64)
+                    .collect();
+                load.push((
+                    columns.clone(),
+                    load_result.to_row(actor.name()),
+                    poll_durations,
+                ));
+                if let Some(runtime_result) = &load_result.runtime {
+                    runtime_metrics.push((columns.clone(), runtime_result.to_row(actor.name())));
+                }
+                report.add(&name, actor.name(), result);
+            }
+        }
+    }
+
+    if throughput.is_empty() && load.is_empty() {
+        return;
+    }
+
+    if !throughput.is_empty() {
+        let mut table = comparison_table(throughput);
+        config.print_table(&mut table);
+        report.add_table("Throughput & Memory Usage", &table);
+
+        allocations.sort_by_key(|(_, row)| row.allocations);
+        let mut table = Table::new(allocations);
+        table.modify(Rows::one(1), Color::BOLD);
+        config.print_table(&mut table);
+        report.add_table("Allocations", &table);
+    }
+
+    if !load.is_empty() {
+        let mut table = comparison_table(load);
+        config.print_table(&mut table);
+        report.add_table("Load (CPU)", &table);
+    }
+
+    if !runtime_metrics.is_empty() {
+        runtime_metrics.sort_by(|(_, a), (_, b)| a.busy_ratio.total_cmp(&b.busy_ratio));
+        let mut table = Table::new(runtime_metrics);
+        table.modify(Rows::one(1), Color::BOLD);
+        config.print_table(&mut table);
+        report.add_table("Runtime metrics", &table);
+    }
+
+    report.finish();
+}
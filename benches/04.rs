@@ -0,0 +1,406 @@
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tabled::{Table, Tabled};
+use tokio::{
+    sync::mpsc,
+    time::{MissedTickBehavior, interval_at},
+};
+
+use async_rust_benchmarks::{
+    _01::{Actor, select::RandomSelectActor},
+    _04::{
+        CpuJob, Offload, OffloadActor,
+        offload::{BlockInPlace, Inline, SpawnBlocking, ThreadPool},
+    },
+    harness::{
+        Bencher, Config, Flavor, Histogram, LatencyResult, Report, RuntimeConfig, Runtimes,
+        Scenario, as_nanos, as_optional_nanos, format_duration, format_throughput,
+    },
+};
+
+/// The job sizes to sweep.
+const JOB_SIZES: [Duration; 11] = [
+    Duration::from_nanos(500),
+    Duration::from_micros(1),
+    Duration::from_micros(5),
+    Duration::from_micros(10),
+    Duration::from_micros(50),
+    Duration::from_micros(100),
+    Duration::from_micros(500),
+    Duration::from_millis(1),
+    Duration::from_millis(5),
+    Duration::from_millis(10),
+    Duration::from_millis(25),
+];
+
+/// The amount of CPU work per iteration. The number of jobs is derived from this.
+const WORK_PER_ITERATION: Duration = Duration::from_millis(100);
+/// The maximum number of jobs per iteration, to bound the duration of the small job sizes.
+const MAX_JOBS: usize = 10000;
+
+/// The interval at which the neighbouring actor receives tasks.
+const NEIGHBOUR_INTERVAL: Duration = Duration::from_millis(1);
+/// The number of tasks the neighbouring actor receives per iteration, spanning the same amount of
+/// time as the CPU work.
+const NEIGHBOUR_TASKS: usize = 100;
+
+const STRATEGIES: [&str; 4] = ["Inline", "SpawnBlocking", "BlockInPlace", "ThreadPool"];
+
+/// The scenarios that can be selected. Throughput and latency are always measured, and the
+/// slow-consumer scenario adds the `slow_consumer_throughput` column.
+const SCENARIOS: [Scenario; 3] = [
+    Scenario::Throughput,
+    Scenario::Latency,
+    Scenario::SlowConsumer,
+];
+
+/// The options of [`Config`] that this benchmark doesn't use.
+const UNSUPPORTED_FLAGS: [&str; 7] = [
+    "--tasks",
+    "--task-duration",
+    "--capacity",
+    "--rate",
+    "--arrival",
+    "--runtime-metrics",
+    "--sweep",
+];
+
+fn num_jobs(job_size: Duration) -> usize {
+    ((WORK_PER_ITERATION.as_nanos() / job_size.as_nanos()) as usize).clamp(1, MAX_JOBS)
+}
+
+/// Measures the throughput of CPU jobs of `job_size` with the [`Offload`] strategy. With
+/// `slow_consumer`, the results channel only has room for `--result-capacity` results, and the
+/// consumer spends `--consumer-delay` on every result, like in the slow-consumer scenario.
+fn benchmark_jobs<S: Offload>(
+    runtimes: &Runtimes,
+    config: &Config,
+    strategy: S,
+    job_size: Duration,
+    slow_consumer: bool,
+) -> f64 {
+    let num_jobs = num_jobs(job_size);
+    let (result_capacity, consumer_delay) = match slow_consumer {
+        true => (config.result_capacity, config.consumer_delay),
+        false => (num_jobs, Duration::ZERO),
+    };
+    let (task_sender, task_receiver) = mpsc::channel(num_jobs);
+    let (result_sender, result_receiver) = mpsc::channel(result_capacity);
+
+    let actor = OffloadActor::new(
+        task_receiver,
+        strategy,
+        CpuJob::new(job_size),
+        result_sender,
+    );
+
+    let mut bencher = Bencher::new(&runtimes.actor, task_sender, result_receiver)
+        .with_producer(runtimes.producer())
+        .with_warmup(config.warmup_iterations)
+        .with_consumer_delay(consumer_delay);
+    bencher
+        .benchmark_throughput(actor, num_jobs, config.iterations)
+        .mean_throughput()
+}
+
+/// Measures the latency of a [`RandomSelectActor`] that runs on the same runtime as an actor that
+/// processes CPU jobs of `job_size` with the [`Offload`] strategy.
+///
+/// The neighbour receives a task every [`NEIGHBOUR_INTERVAL`], and the latency is measured from the
+/// time the task was supposed to be sent. Otherwise, a blocked runtime would also delay the sender,
+/// hiding the latency.
+fn benchmark_neighbour<S: Offload>(
+    runtimes: &Runtimes,
+    config: &Config,
+    strategy: S,
+    job_size: Duration,
+) -> LatencyResult {
+    let num_jobs = num_jobs(job_size);
+    let (job_sender, job_receiver) = mpsc::channel(num_jobs);
+    let (job_result_sender, mut job_result_receiver) = mpsc::channel(num_jobs);
+    let (task_sender, task_receiver) = mpsc::channel(NEIGHBOUR_TASKS);
+    let (result_sender, mut result_receiver) = mpsc::channel(NEIGHBOUR_TASKS);
+
+    let actor = OffloadActor::new(
+        job_receiver,
+        strategy,
+        CpuJob::new(job_size),
+        job_result_sender,
+    );
+    let neighbour = RandomSelectActor::new(task_receiver, result_sender);
+
+    let (rt, producer) = (&runtimes.actor, runtimes.producer());
+    rt.spawn(actor.run());
+    rt.spawn(neighbour.run());
+
+    let mut iterations = Vec::with_capacity(config.iterations);
+
+    for iteration in 0..config.warmup_iterations + config.iterations {
+        // Fix the schedule of the neighbour before the jobs can block the runtime.
+        let start = tokio::time::Instant::now();
+
+        let sender = job_sender.clone();
+        producer.spawn(async move {
+            for _ in 0..num_jobs {
+                sender.send(Instant::now()).await.unwrap();
+            }
+        });
+
+        let sender = task_sender.clone();
+        producer.spawn(async move {
+            let mut interval = interval_at(start, NEIGHBOUR_INTERVAL);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Burst);
+
+            for _ in 0..NEIGHBOUR_TASKS {
+                let scheduled = interval.tick().await;
+                sender.send(scheduled.into_std()).await.unwrap();
+            }
+        });
+
+        let mut histogram = Histogram::new();
+        rt.block_on(async {
+            for _ in 0..NEIGHBOUR_TASKS {
+                histogram.record(result_receiver.recv().await.unwrap());
+            }
+
+            for _ in 0..num_jobs {
+                job_result_receiver.recv().await.unwrap();
+            }
+        });
+        if iteration >= config.warmup_iterations {
+            iterations.push(histogram);
+        }
+    }
+
+    LatencyResult::new(iterations, config.outlier_method)
+}
+
+#[derive(Debug, Tabled, Clone, Serialize)]
+struct SweepRow {
+    /// Duration of a single CPU job.
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    job_size: Duration,
+    /// Offload strategy.
+    strategy: &'static str,
+    /// Mean job throughput in jobs per second.
+    #[tabled(display = "format_throughput")]
+    job_throughput: f64,
+    /// Median latency of the neighbouring actor.
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    neighbour_median_latency: Duration,
+    /// 99th percentile latency of the neighbouring actor.
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    neighbour_p99_latency: Duration,
+    /// Mean job throughput with a slow consumer, if the slow-consumer scenario is selected.
+    #[tabled(display = "format_optional_throughput")]
+    slow_consumer_throughput: Option<f64>,
+}
+
+#[derive(Debug, Tabled, Clone, Serialize)]
+struct CrossoverRow {
+    /// Offload strategy.
+    strategy: &'static str,
+    /// Smallest job size from which the strategy has a higher job throughput than inline.
+    #[tabled(display = "format_crossover")]
+    #[serde(serialize_with = "as_optional_nanos")]
+    throughput_crossover: Option<Duration>,
+    /// Smallest job size from which the strategy gives the neighbouring actor a lower p99 latency
+    /// than inline.
+    #[tabled(display = "format_crossover")]
+    #[serde(serialize_with = "as_optional_nanos")]
+    latency_crossover: Option<Duration>,
+}
+
+fn format_optional_throughput(throughput: &Option<f64>) -> String {
+    throughput
+        .as_ref()
+        .map_or("-".to_string(), format_throughput)
+}
+
+fn format_crossover(crossover: &Option<Duration>) -> String {
+    crossover.as_ref().map_or("-".to_string(), format_duration)
+}
+
+/// Returns the smallest job size from which `better` holds for all larger job sizes.
+fn crossover(
+    rows: &[SweepRow],
+    strategy: &str,
+    better: impl Fn(&SweepRow, &SweepRow) -> bool,
+) -> Option<Duration> {
+    let mut crossover = None;
+
+    for job_size in JOB_SIZES.iter().rev() {
+        let row = |name: &str| {
+            rows.iter()
+                .find(|row| row.job_size == *job_size && row.strategy == name)
+        };
+
+        let (Some(row), Some(inline)) = (row(strategy), row("Inline")) else {
+            break;
+        };
+
+        if !better(row, inline) {
+            break;
+        }
+
+        crossover = Some(*job_size);
+    }
+
+    crossover
+}
+
+/// Runs both measurements for a strategy, if it is selected by the filters of `config`.
+/// `Inline` is always run, because it is the baseline for the crossover.
+fn bench<S: Offload>(
+    runtimes: &Runtimes,
+    config: &Config,
+    strategy_name: &'static str,
+    strategy: impl Fn() -> S,
+    job_size: Duration,
+) -> Option<SweepRow> {
+    if strategy_name != "Inline" && !config.matches(&format!("04/{strategy_name}")) {
+        return None;
+    }
+
+    let job_throughput = benchmark_jobs(runtimes, config, strategy(), job_size, false);
+    let latency = benchmark_neighbour(runtimes, config, strategy(), job_size);
+    let slow_consumer_throughput = config
+        .scenarios
+        .contains(&Scenario::SlowConsumer)
+        .then(|| benchmark_jobs(runtimes, config, strategy(), job_size, true));
+
+    Some(SweepRow {
+        job_size,
+        strategy: strategy_name,
+        job_throughput,
+        neighbour_median_latency: latency.quantile(0.5),
+        neighbour_p99_latency: latency.quantile(0.99),
+        slow_consumer_throughput,
+    })
+}
+
+fn main() {
+    let config = Config {
+        iterations: 10,
+        // The job throughput and the neighbour latency are always measured together.
+        scenarios: vec![Scenario::Throughput, Scenario::Latency],
+        // `block_in_place` is not supported on the current-thread runtime.
+        runtimes: vec![RuntimeConfig::multi_thread()],
+        ..Config::new("04")
+    }
+    .with_env_args();
+
+    if let Some(runtime) = config
+        .runtimes
+        .iter()
+        .find(|runtime| runtime.flavor == Flavor::CurrentThread)
+    {
+        eprintln!(
+            "error: runtime `{runtime}` is not supported, `block_in_place` needs multi-thread"
+        );
+        std::process::exit(2);
+    }
+
+    if let Some(scenario) = config
+        .scenarios
+        .iter()
+        .find(|scenario| !SCENARIOS.contains(scenario))
+    {
+        eprintln!(
+            "error: scenario `{}` is not supported, only `throughput`, `latency` and `slow-consumer`",
+            scenario.name()
+        );
+        std::process::exit(2);
+    }
+
+    if let Some(flag) = config
+        .args
+        .iter()
+        .find(|arg| UNSUPPORTED_FLAGS.contains(&arg.as_str()))
+    {
+        eprintln!("error: `{flag}` is not supported, the job sizes and counts are fixed");
+        std::process::exit(2);
+    }
+    println!("{config}");
+
+    let selected = |strategy: &str| config.matches(&format!("04/{strategy}"));
+    if !STRATEGIES.iter().any(|strategy| selected(strategy)) {
+        return;
+    }
+
+    let mut report = Report::new(&config);
+
+    for runtime in &config.runtimes {
+        println!("Runtime: {runtime}");
+
+        let runtimes = runtime.build();
+        let columns = runtime.columns();
+        let num_workers = runtime.workers();
+        let mut rows = Vec::new();
+
+        for job_size in JOB_SIZES {
+            let sweep = [
+                bench(&runtimes, &config, STRATEGIES[0], || Inline, job_size),
+                bench(
+                    &runtimes,
+                    &config,
+                    STRATEGIES[1],
+                    || SpawnBlocking,
+                    job_size,
+                ),
+                bench(&runtimes, &config, STRATEGIES[2], || BlockInPlace, job_size),
+                bench(
+                    &runtimes,
+                    &config,
+                    STRATEGIES[3],
+                    || ThreadPool::new(num_workers),
+                    job_size,
+                ),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+            let mut table = Table::new(sweep.iter().map(|row| (columns.clone(), row)));
+            config.print_table(&mut table);
+            rows.extend(sweep);
+        }
+
+        let mut table = Table::new(rows.iter().map(|row| (columns.clone(), row)));
+        config.print_table(&mut table);
+        report.add_table(format!("Sweep ({runtime})"), &table);
+
+        let crossovers = STRATEGIES[1..]
+            .iter()
+            .filter(|strategy| rows.iter().any(|row| row.strategy == **strategy))
+            .map(|strategy| CrossoverRow {
+                strategy,
+                throughput_crossover: crossover(&rows, strategy, |a, b| {
+                    a.job_throughput > b.job_throughput
+                }),
+                latency_crossover: crossover(&rows, strategy, |a, b| {
+                    a.neighbour_p99_latency < b.neighbour_p99_latency
+                }),
+            })
+            .collect::<Vec<_>>();
+
+        let mut table = Table::new(crossovers.iter().map(|row| (columns.clone(), row)));
+        config.print_table(&mut table);
+        report.add_table(format!("Crossover ({runtime})"), &table);
+
+        let name = runtime.to_string();
+        for row in &rows {
+            let case = format!("{}/{}", row.strategy, format_duration(&row.job_size));
+            report.add_row(&name, &case, "sweep", row);
+        }
+        for row in &crossovers {
+            report.add_row(&name, row.strategy, "crossover", row);
+        }
+    }
+
+    report.finish();
+}
@@ -0,0 +1,198 @@
+use std::{
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use futures::{StreamExt, stream::FuturesUnordered};
+use tokio::{sync::mpsc, task::coop::unconstrained};
+use tokio_util::sync::PollSender;
+
+use super::{
+    Actor, ActorMetrics, Outbox, Task,
+    future::{Constrained, Unconstrained},
+    task_duration,
+};
+
+/// The default maximum number of tasks that the batching actors receive at once.
+pub const DEFAULT_BATCH_SIZE: usize = 64;
+
+/// The [`FutureActor`](super::future::FutureActor), except that it receives up to `batch_size`
+/// tasks at once with [`mpsc::Receiver::poll_recv_many`], into a buffer that is reused for every
+/// batch.
+///
+/// # Poll order
+/// 1. Send finished results on the results channel, while it has room
+/// 2. Continue work in progress
+/// 3. Receive a batch of new tasks from the incoming channel
+///
+/// Steps 2 and 3 are skipped while the [`Outbox`] is full.
+pub struct BatchFutureActor<T> {
+    pub incoming_tasks: mpsc::Receiver<Instant>,
+    pub batch: Vec<Instant>,
+    pub batch_size: usize,
+    pub processing_tasks: FuturesUnordered<Task>,
+    pub results: PollSender<Duration>,
+    pub outbox: Outbox,
+    pub metrics: ActorMetrics,
+    pub _unconstrained: PhantomData<T>,
+}
+
+impl<T> BatchFutureActor<T> {
+    pub fn new(incoming_tasks: mpsc::Receiver<Instant>, results: mpsc::Sender<Duration>) -> Self {
+        Self {
+            incoming_tasks,
+            batch: Vec::with_capacity(DEFAULT_BATCH_SIZE),
+            batch_size: DEFAULT_BATCH_SIZE,
+            processing_tasks: FuturesUnordered::new(),
+            results: PollSender::new(results),
+            outbox: Outbox::new(),
+            metrics: ActorMetrics::new(),
+            _unconstrained: PhantomData,
+        }
+    }
+
+    /// # Panics
+    /// Panics if `batch_size` is zero.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "the batch size must be at least 1");
+        self.batch = Vec::with_capacity(batch_size);
+        self.batch_size = batch_size;
+        self
+    }
+}
+
+impl Actor for BatchFutureActor<Constrained> {
+    fn run(self) -> impl Future<Output = ActorMetrics> + Send + 'static {
+        self
+    }
+}
+
+impl Actor for BatchFutureActor<Unconstrained> {
+    fn run(self) -> impl Future<Output = ActorMetrics> + Send + 'static {
+        unconstrained(self)
+    }
+}
+
+impl<T> Future for BatchFutureActor<T>
+where
+    T: Unpin,
+{
+    type Output = ActorMetrics;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        loop {
+            // If the results channel is full, this registers the waker to continue once the
+            // consumer has made room.
+            let _ = this.outbox.poll_send(&mut this.results, cx);
+            if this.outbox.is_full() {
+                return Poll::Pending;
+            }
+
+            if let Poll::Ready(Some(result)) = this.processing_tasks.poll_next_unpin(cx) {
+                this.outbox.push(result);
+                this.metrics.max_outbox_len = this.outbox.len().max(this.metrics.max_outbox_len);
+
+                continue;
+            }
+
+            match this
+                .incoming_tasks
+                .poll_recv_many(cx, &mut this.batch, this.batch_size)
+            {
+                // The batch size is never zero, so nothing is received only once the channel
+                // is closed.
+                Poll::Ready(0) => {
+                    return Poll::Ready(this.metrics.clone());
+                }
+                Poll::Ready(_) => {
+                    for task in this.batch.drain(..) {
+                        this.processing_tasks.push(Task::new(task, task_duration()));
+                    }
+                    this.metrics.max_pending_tasks = this
+                        .processing_tasks
+                        .len()
+                        .max(this.metrics.max_pending_tasks);
+
+                    continue;
+                }
+                Poll::Pending => {}
+            }
+
+            return Poll::Pending;
+        }
+    }
+}
+
+/// The [`RandomSelectActor`](super::select::RandomSelectActor), except that it receives up to
+/// `batch_size` tasks at once with [`mpsc::Receiver::recv_many`], into a buffer that is reused
+/// for every batch.
+pub struct BatchSelectActor {
+    pub incoming_tasks: mpsc::Receiver<Instant>,
+    pub batch: Vec<Instant>,
+    pub batch_size: usize,
+    pub processing_tasks: FuturesUnordered<Task>,
+    pub results: mpsc::Sender<Duration>,
+    pub outbox: Outbox,
+    pub metrics: ActorMetrics,
+}
+
+impl BatchSelectActor {
+    pub fn new(incoming_tasks: mpsc::Receiver<Instant>, results: mpsc::Sender<Duration>) -> Self {
+        Self {
+            incoming_tasks,
+            batch: Vec::with_capacity(DEFAULT_BATCH_SIZE),
+            batch_size: DEFAULT_BATCH_SIZE,
+            processing_tasks: FuturesUnordered::new(),
+            results,
+            outbox: Outbox::new(),
+            metrics: ActorMetrics::new(),
+        }
+    }
+
+    /// # Panics
+    /// Panics if `batch_size` is zero.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "the batch size must be at least 1");
+        self.batch = Vec::with_capacity(batch_size);
+        self.batch_size = batch_size;
+        self
+    }
+}
+
+impl Actor for BatchSelectActor {
+    async fn run(mut self) -> ActorMetrics {
+        loop {
+            tokio::select! {
+                received = self.incoming_tasks.recv_many(&mut self.batch, self.batch_size), if !self.outbox.is_full() => {
+                    // The batch size is never zero, so nothing is received only once the
+                    // channel is closed.
+                    if received == 0 {
+                        return self.metrics;
+                    }
+
+                    for task in self.batch.drain(..) {
+                        self.processing_tasks.push(Task::new(task, task_duration()));
+                    }
+                    self.metrics.max_pending_tasks = self
+                        .processing_tasks
+                        .len()
+                        .max(self.metrics.max_pending_tasks);
+                }
+
+                Some(result) = self.processing_tasks.next(), if !self.outbox.is_full() => {
+                    self.outbox.push(result);
+                    self.metrics.max_outbox_len = self.outbox.len().max(self.metrics.max_outbox_len);
+                }
+
+                permit = self.results.reserve(), if !self.outbox.is_empty() => {
+                    self.outbox.send(permit.unwrap());
+                }
+            }
+        }
+    }
+}
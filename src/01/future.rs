@@ -8,21 +8,28 @@ use std::{
 
 use futures::{StreamExt, stream::FuturesUnordered};
 use tokio::{sync::mpsc, task::coop::unconstrained};
+use tokio_util::sync::PollSender;
 
-use super::{Actor, ActorMetrics, TASK_DURATION, Task};
+use super::{Actor, ActorMetrics, Outbox, Task, task_duration};
 
 /// A simple actor that implements the [`Future`] trait.
 /// It will receive tasks from a buffered channel and process them in parallel.
 /// The task consists of multiplying a number by 2, with an artificial async delay of 1ms per task.
 ///
 /// # Poll order
-/// 1. Continue work in progress
-/// 2. Send finished results on the results channel
-/// 3. Receive new tasks from the incoming channel
+/// 1. Send finished results on the results channel, while it has room
+/// 2. Continue work in progress, or receive new tasks from the incoming channel, in the
+///    [`PollOrder`] of the actor
+///
+/// Step 2 is skipped while the [`Outbox`] is full. The actor keeps polling until nothing is
+/// ready, or until its [`Budget`] is used up.
 pub struct FutureActor<T> {
     pub incoming_tasks: mpsc::Receiver<Instant>,
     pub processing_tasks: FuturesUnordered<Task>,
-    pub results: mpsc::Sender<Duration>,
+    pub results: PollSender<Duration>,
+    pub outbox: Outbox,
+    pub poll_order: PollOrder,
+    pub budget: Budget,
     pub metrics: ActorMetrics,
     pub _unconstrained: PhantomData<T>,
 }
@@ -32,11 +39,68 @@ impl<T> FutureActor<T> {
         Self {
             incoming_tasks,
             processing_tasks: FuturesUnordered::new(),
-            results,
+            results: PollSender::new(results),
+            outbox: Outbox::new(),
+            poll_order: PollOrder::default(),
+            budget: Budget::UNLIMITED,
             metrics: ActorMetrics::new(),
             _unconstrained: PhantomData,
         }
     }
+
+    pub fn with_poll_order(mut self, poll_order: PollOrder) -> Self {
+        self.poll_order = poll_order;
+        self
+    }
+
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = budget;
+        self
+    }
+}
+
+/// The order in which a [`FutureActor`] polls its work in progress and its incoming tasks.
+///
+/// After every finished task or received task, the actor starts over with the first source of
+/// the order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PollOrder {
+    /// Finish work in progress before receiving new tasks.
+    #[default]
+    ResultsFirst,
+    /// Receive new tasks before finishing work in progress.
+    IntakeFirst,
+    /// Alternate between both, starting with the one that didn't make progress last.
+    RoundRobin,
+}
+
+/// The work a [`FutureActor`] does in a single poll. Once either limit is reached, the actor
+/// wakes itself and yields to the runtime, instead of polling until nothing is ready.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Budget {
+    /// Maximum number of finished tasks collected per poll.
+    pub completions: usize,
+    /// Maximum number of new tasks received per poll.
+    pub intakes: usize,
+}
+
+impl Budget {
+    /// Polls until nothing is ready. A constrained actor still yields when the cooperative
+    /// budget of tokio is used up.
+    pub const UNLIMITED: Budget = Budget::new(usize::MAX, usize::MAX);
+
+    pub const fn new(completions: usize, intakes: usize) -> Self {
+        Self {
+            completions,
+            intakes,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Source {
+    Results,
+    Intake,
 }
 
 #[derive(Default)]
@@ -65,31 +129,66 @@ where
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.get_mut();
+        let (mut completions, mut intakes) = (0, 0);
+        // Only used by the round-robin order.
+        let mut intake_next = false;
 
-        loop {
-            if let Poll::Ready(Some(result)) = this.processing_tasks.poll_next_unpin(cx) {
-                // Offset the result by the task duration to get the actual latency.
-                this.results
-                    .try_send(Instant::now().duration_since(result) - TASK_DURATION)
-                    .unwrap();
-
-                continue;
+        'poll: loop {
+            // If the results channel is full, this registers the waker to continue once the
+            // consumer has made room.
+            let _ = this.outbox.poll_send(&mut this.results, cx);
+            if this.outbox.is_full() {
+                return Poll::Pending;
             }
 
-            match this.incoming_tasks.poll_recv(cx) {
-                Poll::Ready(Some(task)) => {
-                    this.processing_tasks.push(Task::new(task, TASK_DURATION));
-                    this.metrics.max_pending_tasks = this
-                        .processing_tasks
-                        .len()
-                        .max(this.metrics.max_pending_tasks);
+            let order = match this.poll_order {
+                PollOrder::ResultsFirst => [Source::Results, Source::Intake],
+                PollOrder::IntakeFirst => [Source::Intake, Source::Results],
+                PollOrder::RoundRobin if intake_next => [Source::Intake, Source::Results],
+                PollOrder::RoundRobin => [Source::Results, Source::Intake],
+            };
 
-                    continue;
-                }
-                Poll::Ready(None) => {
-                    return Poll::Ready(this.metrics.clone());
+            let mut exhausted = false;
+            for source in order {
+                match source {
+                    Source::Results if completions >= this.budget.completions => exhausted = true,
+                    Source::Results => {
+                        if let Poll::Ready(Some(result)) = this.processing_tasks.poll_next_unpin(cx)
+                        {
+                            this.outbox.push(result);
+                            this.metrics.max_outbox_len =
+                                this.outbox.len().max(this.metrics.max_outbox_len);
+                            completions += 1;
+                            intake_next = true;
+
+                            continue 'poll;
+                        }
+                    }
+                    Source::Intake if intakes >= this.budget.intakes => exhausted = true,
+                    Source::Intake => match this.incoming_tasks.poll_recv(cx) {
+                        Poll::Ready(Some(task)) => {
+                            this.processing_tasks.push(Task::new(task, task_duration()));
+                            this.metrics.max_pending_tasks = this
+                                .processing_tasks
+                                .len()
+                                .max(this.metrics.max_pending_tasks);
+                            intakes += 1;
+                            intake_next = false;
+
+                            continue 'poll;
+                        }
+                        Poll::Ready(None) => {
+                            return Poll::Ready(this.metrics.clone());
+                        }
+                        Poll::Pending => {}
+                    },
                 }
-                Poll::Pending => {}
+            }
+
+            // A source that was skipped because of the budget hasn't registered the waker, so
+            // make sure the actor is polled again.
+            if exhausted {
+                cx.waker().wake_by_ref();
             }
 
             return Poll::Pending;
@@ -1,15 +1,38 @@
 use pin_project_lite::pin_project;
 use std::{
+    collections::VecDeque,
     pin::Pin,
-    task::{Context, Poll},
+    sync::atomic::{AtomicU64, Ordering},
+    task::{Context, Poll, ready},
     time::{Duration, Instant},
 };
-use tokio::time::Sleep;
+use tokio::{sync::mpsc::Permit, time::Sleep};
+use tokio_util::sync::PollSender;
 
+pub mod batch;
 pub mod future;
 pub mod select;
+pub mod spawn;
+pub mod stream;
 
-const TASK_DURATION: Duration = Duration::from_micros(10);
+/// The default duration of a [`Task`].
+pub const DEFAULT_TASK_DURATION: Duration = Duration::from_micros(10);
+
+static TASK_DURATION_NANOS: AtomicU64 = AtomicU64::new(DEFAULT_TASK_DURATION.as_nanos() as u64);
+
+/// The maximum number of finished results an actor holds while the results channel is full.
+pub const OUTBOX_CAPACITY: usize = 64;
+
+/// Returns the duration of the [`Task`]s created by the actors.
+pub fn task_duration() -> Duration {
+    Duration::from_nanos(TASK_DURATION_NANOS.load(Ordering::Relaxed))
+}
+
+/// Overrides the duration of the [`Task`]s created by the actors. This should be called before
+/// any actor is started, because the actors also use it to offset their latency measurements.
+pub fn set_task_duration(duration: Duration) {
+    TASK_DURATION_NANOS.store(duration.as_nanos() as u64, Ordering::Relaxed);
+}
 
 // We use `pin_project` here because `Sleep` is not `Unpin`. This means that the only way to
 // use it in a `Future` is to put it on the heap with `Box::pin`, which we want to avoid.
@@ -22,7 +45,7 @@ pin_project! {
 }
 
 impl Task {
-    fn new(value: Instant, duration: Duration) -> Self {
+    pub fn new(value: Instant, duration: Duration) -> Self {
         Self {
             sleep: tokio::time::sleep(duration),
             value,
@@ -42,21 +65,117 @@ impl Future for Task {
     }
 }
 
+/// Finished results that wait for room in the results channel.
+///
+/// The actors keep their results here instead of failing on a full results channel. The outbox
+/// holds at most [`OUTBOX_CAPACITY`] results: once it is full, an actor stops receiving new tasks
+/// and finishing running ones until the consumer catches up, so a slow consumer slows down the
+/// producer through the task channel instead of growing the actor without bounds.
+#[derive(Debug)]
+pub struct Outbox {
+    results: VecDeque<Instant>,
+    /// Subtracted from the time since a task was sent to get its latency.
+    offset: Duration,
+}
+
+impl Outbox {
+    /// Creates an outbox for [`Task`] results, which are offset by the task duration.
+    pub fn new() -> Self {
+        Self::with_offset(task_duration())
+    }
+
+    /// Creates an outbox whose latencies are the full time since a task was sent, minus
+    /// `offset`.
+    pub fn with_offset(offset: Duration) -> Self {
+        Self {
+            results: VecDeque::with_capacity(OUTBOX_CAPACITY),
+            offset,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.results.len() >= OUTBOX_CAPACITY
+    }
+
+    /// Adds the result of a finished task, i.e. the time the task was sent.
+    pub fn push(&mut self, result: Instant) {
+        self.results.push_back(result);
+    }
+
+    /// Sends the oldest result with a `permit` of the results channel.
+    ///
+    /// # Panics
+    /// Panics if the outbox is empty.
+    pub fn send(&mut self, permit: Permit<'_, Duration>) {
+        let result = self.results.pop_front().expect("the outbox is empty");
+        permit.send(Instant::now().duration_since(result) - self.offset);
+    }
+
+    /// Sends results until the outbox is empty, or the results channel is full. In the latter
+    /// case, the current task is woken once the channel has room again.
+    ///
+    /// # Panics
+    /// Panics if the results channel is closed, which the benchmarks never do while an actor
+    /// is running.
+    pub fn poll_send(
+        &mut self,
+        results: &mut PollSender<Duration>,
+        cx: &mut Context<'_>,
+    ) -> Poll<()> {
+        while let Some(&result) = self.results.front() {
+            ready!(results.poll_reserve(cx)).unwrap();
+            self.results.pop_front();
+            results
+                .send_item(Instant::now().duration_since(result) - self.offset)
+                .unwrap();
+        }
+
+        Poll::Ready(())
+    }
+}
+
+impl Default for Outbox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the latency of a task that was sent at `sent` and is being sent on the results
+/// channel now. The result is offset by the task duration to get the actual latency.
+fn latency(sent: Instant) -> Duration {
+    Instant::now().duration_since(sent) - task_duration()
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct ActorMetrics {
-    max_pending_tasks: usize,
+    pub(crate) max_pending_tasks: usize,
+    pub(crate) max_outbox_len: usize,
 }
 
 impl ActorMetrics {
     pub fn new() -> Self {
         Self {
             max_pending_tasks: 0,
+            max_outbox_len: 0,
         }
     }
 
     pub fn max_pending_tasks(&self) -> usize {
         self.max_pending_tasks
     }
+
+    /// The highest number of finished results that waited for room in the results channel.
+    pub fn max_outbox_len(&self) -> usize {
+        self.max_outbox_len
+    }
 }
 
 pub trait Actor {
@@ -2,23 +2,36 @@ use futures::{StreamExt, stream::FuturesUnordered};
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
-use super::{Actor, ActorMetrics, TASK_DURATION, Task};
+use super::{Actor, ActorMetrics, Outbox, Task, task_duration};
 
 pub struct RandomSelectActor {
     pub incoming_tasks: mpsc::Receiver<Instant>,
     pub processing_tasks: FuturesUnordered<Task>,
     pub results: mpsc::Sender<Duration>,
+    pub outbox: Outbox,
     pub metrics: ActorMetrics,
 }
 
+impl RandomSelectActor {
+    pub fn new(incoming_tasks: mpsc::Receiver<Instant>, results: mpsc::Sender<Duration>) -> Self {
+        Self {
+            incoming_tasks,
+            processing_tasks: FuturesUnordered::new(),
+            results,
+            outbox: Outbox::new(),
+            metrics: ActorMetrics::new(),
+        }
+    }
+}
+
 impl Actor for RandomSelectActor {
     async fn run(mut self) -> ActorMetrics {
         loop {
             tokio::select! {
-                task = self.incoming_tasks.recv() => {
+                task = self.incoming_tasks.recv(), if !self.outbox.is_full() => {
                     match task {
                         Some(task) => {
-                            self.processing_tasks.push(Task::new(task, TASK_DURATION));
+                            self.processing_tasks.push(Task::new(task, task_duration()));
                             self.metrics.max_pending_tasks = self
                                 .processing_tasks
                                 .len()
@@ -30,9 +43,13 @@ impl Actor for RandomSelectActor {
                     }
                 }
 
-                Some(result) = self.processing_tasks.next() => {
-                    // Offset the result by the task duration to get the actual latency.
-                    self.results.try_send(Instant::now().duration_since(result) - TASK_DURATION).unwrap();
+                Some(result) = self.processing_tasks.next(), if !self.outbox.is_full() => {
+                    self.outbox.push(result);
+                    self.metrics.max_outbox_len = self.outbox.len().max(self.metrics.max_outbox_len);
+                }
+
+                permit = self.results.reserve(), if !self.outbox.is_empty() => {
+                    self.outbox.send(permit.unwrap());
                 }
             }
         }
@@ -43,9 +60,22 @@ pub struct BiasedSelectActor {
     pub incoming_tasks: mpsc::Receiver<Instant>,
     pub processing_tasks: FuturesUnordered<Task>,
     pub results: mpsc::Sender<Duration>,
+    pub outbox: Outbox,
     pub metrics: ActorMetrics,
 }
 
+impl BiasedSelectActor {
+    pub fn new(incoming_tasks: mpsc::Receiver<Instant>, results: mpsc::Sender<Duration>) -> Self {
+        Self {
+            incoming_tasks,
+            processing_tasks: FuturesUnordered::new(),
+            results,
+            outbox: Outbox::new(),
+            metrics: ActorMetrics::new(),
+        }
+    }
+}
+
 impl Actor for BiasedSelectActor {
     async fn run(mut self) -> ActorMetrics {
         loop {
@@ -54,15 +84,19 @@ impl Actor for BiasedSelectActor {
                 // But it would also incur the highest memory usage.
                 biased;
 
-                Some(result) = self.processing_tasks.next() => {
-                    // Offset the result by the task duration to get the actual latency.
-                    self.results.try_send(Instant::now().duration_since(result) - TASK_DURATION).unwrap();
+                permit = self.results.reserve(), if !self.outbox.is_empty() => {
+                    self.outbox.send(permit.unwrap());
+                }
+
+                Some(result) = self.processing_tasks.next(), if !self.outbox.is_full() => {
+                    self.outbox.push(result);
+                    self.metrics.max_outbox_len = self.outbox.len().max(self.metrics.max_outbox_len);
                 }
 
-                task = self.incoming_tasks.recv() => {
+                task = self.incoming_tasks.recv(), if !self.outbox.is_full() => {
                     match task {
                         Some(task) => {
-                            self.processing_tasks.push(Task::new(task, TASK_DURATION));
+                            self.processing_tasks.push(Task::new(task, task_duration()));
                             self.metrics.max_pending_tasks = self
                                 .processing_tasks
                                 .len()
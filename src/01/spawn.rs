@@ -0,0 +1,111 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use tokio::{sync::mpsc, task::JoinSet};
+
+use super::{Actor, ActorMetrics, Task, latency, task_duration};
+
+/// Processes a single task, and sends its result. Waits for room in the results channel, so
+/// the results of a slow consumer pile up in the spawned tasks instead of an outbox.
+async fn process(task: Instant, results: mpsc::Sender<Duration>) {
+    let result = Task::new(task, task_duration()).await;
+    let permit = results.reserve().await.unwrap();
+    permit.send(latency(result));
+}
+
+/// An actor that spawns every task with [`tokio::spawn`], and lets it send its own result.
+/// The spawned tasks are not tracked, except for a counter of the tasks in flight.
+pub struct SpawnActor {
+    pub incoming_tasks: mpsc::Receiver<Instant>,
+    pub results: mpsc::Sender<Duration>,
+    /// Number of spawned tasks that haven't sent their result yet.
+    pub in_flight: Arc<AtomicUsize>,
+    pub metrics: ActorMetrics,
+}
+
+impl SpawnActor {
+    pub fn new(incoming_tasks: mpsc::Receiver<Instant>, results: mpsc::Sender<Duration>) -> Self {
+        Self {
+            incoming_tasks,
+            results,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            metrics: ActorMetrics::new(),
+        }
+    }
+}
+
+impl Actor for SpawnActor {
+    async fn run(mut self) -> ActorMetrics {
+        while let Some(task) = self.incoming_tasks.recv().await {
+            // Counted before spawning, so the task can't finish before it is counted.
+            let pending = self.in_flight.fetch_add(1, Ordering::Relaxed) + 1;
+            self.metrics.max_pending_tasks = pending.max(self.metrics.max_pending_tasks);
+
+            let (results, in_flight) = (self.results.clone(), self.in_flight.clone());
+            tokio::spawn(async move {
+                process(task, results).await;
+                in_flight.fetch_sub(1, Ordering::Relaxed);
+            });
+        }
+
+        self.metrics
+    }
+}
+
+/// The [`SpawnActor`], except that the tasks are spawned on a [`JoinSet`], which the actor
+/// drains of finished tasks between receiving new ones, and waits for before it returns.
+pub struct SpawnJoinSetActor {
+    pub incoming_tasks: mpsc::Receiver<Instant>,
+    pub processing_tasks: JoinSet<()>,
+    pub results: mpsc::Sender<Duration>,
+    pub metrics: ActorMetrics,
+}
+
+impl SpawnJoinSetActor {
+    pub fn new(incoming_tasks: mpsc::Receiver<Instant>, results: mpsc::Sender<Duration>) -> Self {
+        Self {
+            incoming_tasks,
+            processing_tasks: JoinSet::new(),
+            results,
+            metrics: ActorMetrics::new(),
+        }
+    }
+}
+
+impl Actor for SpawnJoinSetActor {
+    async fn run(mut self) -> ActorMetrics {
+        loop {
+            tokio::select! {
+                task = self.incoming_tasks.recv() => {
+                    match task {
+                        Some(task) => {
+                            self.processing_tasks.spawn(process(task, self.results.clone()));
+                            self.metrics.max_pending_tasks = self
+                                .processing_tasks
+                                .len()
+                                .max(self.metrics.max_pending_tasks);
+                        }
+                        None => {
+                            // Dropping the `JoinSet` would abort the tasks in flight, so wait
+                            // for them to send their results first.
+                            while let Some(result) = self.processing_tasks.join_next().await {
+                                result.unwrap();
+                            }
+                            return self.metrics;
+                        }
+                    }
+                }
+
+                Some(result) = self.processing_tasks.join_next() => {
+                    // Tasks are never aborted and don't panic.
+                    result.unwrap();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use std::{
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use futures::{
+    Stream, StreamExt, future,
+    stream::{self, FuturesUnordered, PollNext},
+};
+use tokio::sync::mpsc;
+
+use super::{Actor, ActorMetrics, Task, latency, task_duration};
+
+/// An actor that merges its incoming tasks and the completions of its work in progress into a
+/// single stream with [`stream::select_with_strategy`], and handles one [`Event`] at a time with
+/// `while let Some(event) = events.next().await`.
+///
+/// The [`Strategy`] decides which of the two streams is polled first. Sending a result waits for
+/// room in the results channel, which stalls the whole stream, so finished results never pile
+/// up in the actor.
+pub struct StreamActor<S> {
+    pub incoming_tasks: mpsc::Receiver<Instant>,
+    pub results: mpsc::Sender<Duration>,
+    pub metrics: ActorMetrics,
+    pub _strategy: PhantomData<S>,
+}
+
+impl<S> StreamActor<S> {
+    pub fn new(incoming_tasks: mpsc::Receiver<Instant>, results: mpsc::Sender<Duration>) -> Self {
+        Self {
+            incoming_tasks,
+            results,
+            metrics: ActorMetrics::new(),
+            _strategy: PhantomData,
+        }
+    }
+}
+
+/// Picks the stream that [`stream::select_with_strategy`] polls first, where the left stream
+/// is the work in progress and the right stream the incoming tasks.
+pub trait Strategy: Send + 'static {
+    type State: Default + Send;
+
+    fn next(state: &mut Self::State) -> PollNext;
+}
+
+/// Alternates between the work in progress and the incoming tasks.
+#[derive(Default)]
+pub struct RoundRobin;
+
+impl Strategy for RoundRobin {
+    type State = PollNext;
+
+    fn next(last: &mut PollNext) -> PollNext {
+        last.toggle()
+    }
+}
+
+/// Always polls the work in progress first, like the
+/// [`BiasedSelectActor`](super::select::BiasedSelectActor).
+#[derive(Default)]
+pub struct Prioritized;
+
+impl Strategy for Prioritized {
+    type State = ();
+
+    fn next(_: &mut ()) -> PollNext {
+        PollNext::Left
+    }
+}
+
+/// An item of the merged stream of a [`StreamActor`].
+pub enum Event {
+    /// A new task was received.
+    Task(Instant),
+    /// A task is finished.
+    Result(Instant),
+    /// The incoming channel is closed.
+    Closed,
+}
+
+/// An [`mpsc::Receiver`] as a [`Stream`], like `tokio_stream::wrappers::ReceiverStream`.
+pub struct ReceiverStream<T> {
+    receiver: mpsc::Receiver<T>,
+}
+
+impl<T> ReceiverStream<T> {
+    pub fn new(receiver: mpsc::Receiver<T>) -> Self {
+        Self { receiver }
+    }
+}
+
+impl<T> Stream for ReceiverStream<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.get_mut().receiver.poll_recv(cx)
+    }
+}
+
+/// The work in progress as a [`Stream`] that never ends. [`FuturesUnordered`] returns `None`
+/// while it is empty, which would end its side of the merged stream for good.
+struct Processing {
+    tasks: FuturesUnordered<Task>,
+}
+
+impl Stream for Processing {
+    type Item = Event;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Event>> {
+        match self.get_mut().tasks.poll_next_unpin(cx) {
+            Poll::Ready(Some(result)) => Poll::Ready(Some(Event::Result(result))),
+            // New tasks are only pushed by the actor itself, which polls the stream again
+            // afterwards, so there is no need for a wake-up.
+            Poll::Ready(None) | Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<S: Strategy> Actor for StreamActor<S> {
+    async fn run(self) -> ActorMetrics {
+        let Self {
+            incoming_tasks,
+            results,
+            mut metrics,
+            ..
+        } = self;
+
+        let processing = Processing {
+            tasks: FuturesUnordered::new(),
+        };
+        let intake = ReceiverStream::new(incoming_tasks)
+            .map(Event::Task)
+            .chain(stream::once(future::ready(Event::Closed)));
+        let mut events = stream::select_with_strategy(processing, intake, S::next);
+
+        while let Some(event) = events.next().await {
+            match event {
+                Event::Task(task) => {
+                    let (processing, _) = events.get_mut();
+                    processing.tasks.push(Task::new(task, task_duration()));
+                    metrics.max_pending_tasks =
+                        processing.tasks.len().max(metrics.max_pending_tasks);
+                }
+                Event::Result(result) => {
+                    let permit = results.reserve().await.unwrap();
+                    permit.send(latency(result));
+                }
+                Event::Closed => break,
+            }
+        }
+
+        metrics
+    }
+}
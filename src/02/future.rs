@@ -0,0 +1,107 @@
+use std::{
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use tokio::{sync::mpsc, task::JoinSet, task::coop::unconstrained};
+use tokio_util::sync::PollSender;
+
+use crate::_01::{
+    Actor, ActorMetrics, Outbox, Task,
+    future::{Constrained, Unconstrained},
+    task_duration,
+};
+
+/// The [`JoinSet`] equivalent of [`FutureActor`](crate::_01::future::FutureActor).
+///
+/// # Poll order
+/// 1. Send finished results on the results channel, while it has room
+/// 2. Collect finished tasks from the [`JoinSet`]
+/// 3. Receive new tasks from the incoming channel and spawn them
+///
+/// Steps 2 and 3 are skipped while the [`Outbox`] is full.
+pub struct JoinSetFutureActor<T> {
+    pub incoming_tasks: mpsc::Receiver<Instant>,
+    pub processing_tasks: JoinSet<Instant>,
+    pub results: PollSender<Duration>,
+    pub outbox: Outbox,
+    pub metrics: ActorMetrics,
+    pub _unconstrained: PhantomData<T>,
+}
+
+impl<T> JoinSetFutureActor<T> {
+    pub fn new(incoming_tasks: mpsc::Receiver<Instant>, results: mpsc::Sender<Duration>) -> Self {
+        Self {
+            incoming_tasks,
+            processing_tasks: JoinSet::new(),
+            results: PollSender::new(results),
+            outbox: Outbox::new(),
+            metrics: ActorMetrics::new(),
+            _unconstrained: PhantomData,
+        }
+    }
+}
+
+impl Actor for JoinSetFutureActor<Constrained> {
+    fn run(self) -> impl Future<Output = ActorMetrics> + Send + 'static {
+        self
+    }
+}
+
+impl Actor for JoinSetFutureActor<Unconstrained> {
+    fn run(self) -> impl Future<Output = ActorMetrics> + Send + 'static {
+        unconstrained(self)
+    }
+}
+
+impl<T> Future for JoinSetFutureActor<T>
+where
+    T: Unpin,
+{
+    type Output = ActorMetrics;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        loop {
+            // If the results channel is full, this registers the waker to continue once the
+            // consumer has made room.
+            let _ = this.outbox.poll_send(&mut this.results, cx);
+            if this.outbox.is_full() {
+                return Poll::Pending;
+            }
+
+            if let Poll::Ready(Some(result)) = this.processing_tasks.poll_join_next(cx) {
+                // Tasks are never aborted and don't panic.
+                this.outbox.push(result.unwrap());
+                this.metrics.max_outbox_len = this.outbox.len().max(this.metrics.max_outbox_len);
+
+                continue;
+            }
+
+            match this.incoming_tasks.poll_recv(cx) {
+                Poll::Ready(Some(task)) => {
+                    this.processing_tasks
+                        .spawn(Task::new(task, task_duration()));
+                    this.metrics.max_pending_tasks = this
+                        .processing_tasks
+                        .len()
+                        .max(this.metrics.max_pending_tasks);
+
+                    continue;
+                }
+                // Dropping the `JoinSet` would abort the tasks in flight, so the actor only
+                // returns once they are finished and their results are sent.
+                Poll::Ready(None) if this.processing_tasks.is_empty() && this.outbox.is_empty() => {
+                    return Poll::Ready(this.metrics.clone());
+                }
+                Poll::Ready(None) | Poll::Pending => {}
+            }
+
+            return Poll::Pending;
+        }
+    }
+}
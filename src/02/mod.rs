@@ -0,0 +1,9 @@
+//! `JoinSet` vs. `FuturesUnordered` as the work pool of an actor.
+//!
+//! The actors in this module are identical to the ones in [`crate::_01`], except that in-flight
+//! [`Task`]s are spawned onto the runtime and tracked in a [`tokio::task::JoinSet`] instead of
+//! being polled inline in a [`futures::stream::FuturesUnordered`].
+//!
+//! [`Task`]: crate::_01::Task
+pub mod future;
+pub mod select;
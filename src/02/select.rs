@@ -0,0 +1,68 @@
+use std::time::{Duration, Instant};
+use tokio::{sync::mpsc, task::JoinSet};
+
+use crate::_01::{Actor, ActorMetrics, Outbox, Task, task_duration};
+
+/// The [`JoinSet`] equivalent of [`RandomSelectActor`](crate::_01::select::RandomSelectActor).
+/// Every task is spawned onto the runtime, which means it can run in parallel with the actor on a
+/// multi-threaded runtime.
+pub struct JoinSetSelectActor {
+    pub incoming_tasks: mpsc::Receiver<Instant>,
+    pub processing_tasks: JoinSet<Instant>,
+    pub results: mpsc::Sender<Duration>,
+    pub outbox: Outbox,
+    pub metrics: ActorMetrics,
+}
+
+impl JoinSetSelectActor {
+    pub fn new(incoming_tasks: mpsc::Receiver<Instant>, results: mpsc::Sender<Duration>) -> Self {
+        Self {
+            incoming_tasks,
+            processing_tasks: JoinSet::new(),
+            results,
+            outbox: Outbox::new(),
+            metrics: ActorMetrics::new(),
+        }
+    }
+}
+
+impl Actor for JoinSetSelectActor {
+    async fn run(mut self) -> ActorMetrics {
+        let mut closed = false;
+
+        loop {
+            tokio::select! {
+                task = self.incoming_tasks.recv(), if !closed && !self.outbox.is_full() => {
+                    match task {
+                        Some(task) => {
+                            self.processing_tasks.spawn(Task::new(task, task_duration()));
+                            self.metrics.max_pending_tasks = self
+                                .processing_tasks
+                                .len()
+                                .max(self.metrics.max_pending_tasks);
+                        }
+                        None => {
+                            closed = true;
+                        }
+                    }
+                }
+
+                Some(result) = self.processing_tasks.join_next(), if !self.outbox.is_full() => {
+                    // Tasks are never aborted and don't panic.
+                    self.outbox.push(result.unwrap());
+                    self.metrics.max_outbox_len = self.outbox.len().max(self.metrics.max_outbox_len);
+                }
+
+                permit = self.results.reserve(), if !self.outbox.is_empty() => {
+                    self.outbox.send(permit.unwrap());
+                }
+
+                // Dropping the `JoinSet` would abort the tasks in flight, so the actor only
+                // returns once they are finished and their results are sent.
+                else => {
+                    return self.metrics;
+                }
+            }
+        }
+    }
+}
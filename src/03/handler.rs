@@ -0,0 +1,34 @@
+use std::{future::Future, pin::Pin, time::Instant};
+
+use crate::_01::{Task, task_duration};
+
+use super::{DynHandler, Handler};
+
+/// Returns the statically-typed [`Task`] future. No allocations, no dynamic dispatch.
+pub struct StaticHandler;
+
+impl Handler for StaticHandler {
+    fn handle(&self, value: Instant) -> impl Future<Output = Instant> + Send {
+        Task::new(value, task_duration())
+    }
+}
+
+/// A native `async fn` in trait. The future is still statically typed, but it is an
+/// `async` state machine wrapping the [`Task`] instead of the [`Task`] itself.
+pub struct AsyncFnHandler;
+
+impl Handler for AsyncFnHandler {
+    async fn handle(&self, value: Instant) -> Instant {
+        Task::new(value, task_duration()).await
+    }
+}
+
+/// The `async-trait` expansion of [`AsyncFnHandler`]: the `async` block is put on the heap and
+/// returned as a trait object.
+pub struct BoxedHandler;
+
+impl DynHandler for BoxedHandler {
+    fn handle(&self, value: Instant) -> Pin<Box<dyn Future<Output = Instant> + Send + '_>> {
+        Box::pin(async move { Task::new(value, task_duration()).await })
+    }
+}
@@ -0,0 +1,103 @@
+//! The cost of `Pin<Box<dyn Future<Output = ()>>>`.
+//!
+//! All actors in this module run the same [`Task`] workload, but create the futures for it
+//! through a [`Handler`]. The handlers differ only in how the future is typed:
+//! - [`StaticHandler`](handler::StaticHandler): returns the concrete [`Task`].
+//! - [`AsyncFnHandler`](handler::AsyncFnHandler): a native `async fn` in trait.
+//! - [`BoxedHandler`](handler::BoxedHandler): a `Pin<Box<dyn Future + Send>>` behind a trait
+//!   object, which is what `async-trait` expands to.
+use futures::{StreamExt, stream::FuturesUnordered};
+use std::{
+    future::Future,
+    pin::Pin,
+    time::{Duration, Instant},
+};
+use tokio::sync::mpsc;
+
+use crate::_01::{Actor, ActorMetrics, Outbox};
+
+pub mod handler;
+
+/// Creates the future that processes a single task.
+pub trait Handler: Send + Sync + 'static {
+    fn handle(&self, value: Instant) -> impl Future<Output = Instant> + Send;
+}
+
+/// The dyn-compatible version of [`Handler`], as generated by `async-trait`.
+pub trait DynHandler: Send + Sync + 'static {
+    fn handle(&self, value: Instant) -> Pin<Box<dyn Future<Output = Instant> + Send + '_>>;
+}
+
+impl Handler for Box<dyn DynHandler> {
+    fn handle(&self, value: Instant) -> impl Future<Output = Instant> + Send {
+        (**self).handle(value)
+    }
+}
+
+/// A `tokio::select!` actor equivalent to
+/// [`RandomSelectActor`](crate::_01::select::RandomSelectActor), except that it creates its
+/// futures through a [`Handler`].
+pub struct HandlerActor<H> {
+    pub incoming_tasks: mpsc::Receiver<Instant>,
+    pub handler: H,
+    pub results: mpsc::Sender<Duration>,
+    pub metrics: ActorMetrics,
+}
+
+impl<H: Handler> HandlerActor<H> {
+    pub fn new(
+        incoming_tasks: mpsc::Receiver<Instant>,
+        handler: H,
+        results: mpsc::Sender<Duration>,
+    ) -> Self {
+        Self {
+            incoming_tasks,
+            handler,
+            results,
+            metrics: ActorMetrics::new(),
+        }
+    }
+}
+
+impl<H: Handler> Actor for HandlerActor<H> {
+    async fn run(self) -> ActorMetrics {
+        let Self {
+            mut incoming_tasks,
+            handler,
+            results,
+            mut metrics,
+        } = self;
+
+        // The future type returned by `Handler::handle` can't be named, so the work pool lives
+        // on the stack of this future instead of in a field.
+        let mut processing_tasks = FuturesUnordered::new();
+        let mut outbox = Outbox::new();
+
+        loop {
+            tokio::select! {
+                task = incoming_tasks.recv(), if !outbox.is_full() => {
+                    match task {
+                        Some(task) => {
+                            processing_tasks.push(handler.handle(task));
+                            metrics.max_pending_tasks = processing_tasks
+                                .len()
+                                .max(metrics.max_pending_tasks);
+                        }
+                        None => {
+                            return metrics;
+                        }
+                    }
+                }
+
+                Some(result) = processing_tasks.next(), if !outbox.is_full() => {
+                    outbox.push(result);
+                    metrics.max_outbox_len = outbox.len().max(metrics.max_outbox_len);
+                }
+
+                permit = results.reserve(), if !outbox.is_empty() => {
+                    outbox.send(permit.unwrap());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! When to use `spawn_blocking`.
+//!
+//! The actors in this module receive CPU-bound jobs instead of the async [`Task`] workload. Every
+//! job is handed to an [`Offload`] strategy, which decides where the CPU work actually runs:
+//! - [`Inline`](offload::Inline): in the poll of the actor itself.
+//! - [`SpawnBlocking`](offload::SpawnBlocking): on the blocking pool via
+//!   [`tokio::task::spawn_blocking`].
+//! - [`BlockInPlace`](offload::BlockInPlace): in the poll of the actor, after handing off the
+//!   worker thread with [`tokio::task::block_in_place`]. Requires a multi-thread runtime.
+//! - [`ThreadPool`](offload::ThreadPool): on a dedicated pool of [`std::thread`]s fed by a channel.
+//!
+//! [`Task`]: crate::_01::Task
+use futures::{StreamExt, stream::FuturesUnordered};
+use std::{
+    future::Future,
+    hint::black_box,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+use tokio::sync::mpsc;
+
+use crate::_01::{Actor, ActorMetrics, Outbox};
+
+pub mod offload;
+
+/// A CPU-bound job that takes roughly `duration` to run on an otherwise idle core.
+///
+/// The job is a fixed amount of work rather than a busy loop on the clock, so that running jobs
+/// concurrently on an oversubscribed machine takes longer instead of finishing more jobs.
+#[derive(Debug, Clone, Copy)]
+pub struct CpuJob {
+    pub duration: Duration,
+    iterations: u64,
+}
+
+impl CpuJob {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            iterations: (iterations_per_second() * duration.as_secs_f64()) as u64,
+        }
+    }
+
+    /// Runs the job on the current thread.
+    pub fn run(self) {
+        spin(self.iterations);
+    }
+}
+
+fn spin(iterations: u64) -> u64 {
+    let mut x = 0u64;
+    for _ in 0..iterations {
+        x = black_box(x.wrapping_mul(31).wrapping_add(7));
+    }
+    x
+}
+
+/// Calibrates the number of [`spin`] iterations per second on the first call.
+fn iterations_per_second() -> f64 {
+    static RATE: OnceLock<f64> = OnceLock::new();
+
+    *RATE.get_or_init(|| {
+        const CALIBRATION_ITERATIONS: u64 = 10_000_000;
+
+        // Warm up first.
+        spin(CALIBRATION_ITERATIONS);
+
+        let start = Instant::now();
+        spin(CALIBRATION_ITERATIONS);
+        CALIBRATION_ITERATIONS as f64 / start.elapsed().as_secs_f64()
+    })
+}
+
+/// Decides where a [`CpuJob`] is run.
+pub trait Offload: Send + 'static {
+    /// Starts running `job`. The returned future resolves once the job is done.
+    fn offload(&self, job: CpuJob) -> impl Future<Output = ()> + Send + 'static;
+}
+
+/// A `tokio::select!` actor that runs a [`CpuJob`] for every incoming task using the [`Offload`]
+/// strategy `S`.
+///
+/// Unlike the actors in [`crate::_01`], the latency that is sent on the results channel is the
+/// full time from sending the task to finishing the job, including the job itself. Finished jobs
+/// wait for room in the results channel in an [`Outbox`], like in the
+/// [`RandomSelectActor`](crate::_01::select::RandomSelectActor).
+pub struct OffloadActor<S> {
+    pub incoming_tasks: mpsc::Receiver<Instant>,
+    pub strategy: S,
+    pub job: CpuJob,
+    pub results: mpsc::Sender<Duration>,
+    pub metrics: ActorMetrics,
+}
+
+impl<S: Offload> OffloadActor<S> {
+    pub fn new(
+        incoming_tasks: mpsc::Receiver<Instant>,
+        strategy: S,
+        job: CpuJob,
+        results: mpsc::Sender<Duration>,
+    ) -> Self {
+        Self {
+            incoming_tasks,
+            strategy,
+            job,
+            results,
+            metrics: ActorMetrics::new(),
+        }
+    }
+}
+
+impl<S: Offload> Actor for OffloadActor<S> {
+    async fn run(self) -> ActorMetrics {
+        let Self {
+            mut incoming_tasks,
+            strategy,
+            job,
+            results,
+            mut metrics,
+        } = self;
+
+        let mut processing_tasks = FuturesUnordered::new();
+        let mut outbox = Outbox::with_offset(Duration::ZERO);
+
+        loop {
+            tokio::select! {
+                task = incoming_tasks.recv(), if !outbox.is_full() => {
+                    match task {
+                        Some(task) => {
+                            let done = strategy.offload(job);
+                            processing_tasks.push(async move {
+                                done.await;
+                                task
+                            });
+                            metrics.max_pending_tasks = processing_tasks
+                                .len()
+                                .max(metrics.max_pending_tasks);
+                        }
+                        None => {
+                            return metrics;
+                        }
+                    }
+                }
+
+                Some(result) = processing_tasks.next(), if !outbox.is_full() => {
+                    outbox.push(result);
+                    metrics.max_outbox_len = outbox.len().max(metrics.max_outbox_len);
+                }
+
+                permit = results.reserve(), if !outbox.is_empty() => {
+                    outbox.send(permit.unwrap());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use std::{
+    future::{Future, ready},
+    sync::{Arc, Mutex, mpsc},
+    thread,
+};
+
+use tokio::sync::oneshot;
+
+use super::{CpuJob, Offload};
+
+/// Runs the job inline, blocking the runtime thread for the duration of the job.
+pub struct Inline;
+
+impl Offload for Inline {
+    fn offload(&self, job: CpuJob) -> impl Future<Output = ()> + Send + 'static {
+        job.run();
+        ready(())
+    }
+}
+
+/// Runs the job on tokio's blocking thread pool with [`tokio::task::spawn_blocking`].
+pub struct SpawnBlocking;
+
+impl Offload for SpawnBlocking {
+    fn offload(&self, job: CpuJob) -> impl Future<Output = ()> + Send + 'static {
+        let handle = tokio::task::spawn_blocking(move || job.run());
+        async move { handle.await.unwrap() }
+    }
+}
+
+/// Runs the job inline with [`tokio::task::block_in_place`], which moves the other tasks of the
+/// current worker to a new thread first.
+///
+/// # Panics
+/// Panics when used on a current-thread runtime.
+pub struct BlockInPlace;
+
+impl Offload for BlockInPlace {
+    fn offload(&self, job: CpuJob) -> impl Future<Output = ()> + Send + 'static {
+        tokio::task::block_in_place(|| job.run());
+        ready(())
+    }
+}
+
+type PoolJob = (CpuJob, oneshot::Sender<()>);
+
+/// Runs the job on a dedicated pool of [`std::thread`]s. The workers exit once the pool is
+/// dropped.
+pub struct ThreadPool {
+    jobs: mpsc::Sender<PoolJob>,
+}
+
+impl ThreadPool {
+    pub fn new(num_threads: usize) -> Self {
+        let (jobs, receiver) = mpsc::channel::<PoolJob>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for _ in 0..num_threads {
+            let receiver = Arc::clone(&receiver);
+            thread::spawn(move || {
+                loop {
+                    // Release the lock before running the job.
+                    let next = receiver.lock().unwrap().recv();
+                    let Ok((job, done)) = next else {
+                        return;
+                    };
+
+                    job.run();
+                    let _ = done.send(());
+                }
+            });
+        }
+
+        Self { jobs }
+    }
+}
+
+impl Offload for ThreadPool {
+    fn offload(&self, job: CpuJob) -> impl Future<Output = ()> + Send + 'static {
+        let (done, rx) = oneshot::channel();
+        self.jobs.send((job, done)).unwrap();
+        async move { rx.await.unwrap() }
+    }
+}
@@ -0,0 +1,147 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static DEALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+static DEALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+static PEAK_LIVE_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// A [`GlobalAlloc`] wrapper around the [`System`] allocator that counts allocations,
+/// deallocations and the peak number of live bytes. Benchmarks can opt in with:
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOCATOR: CountingAllocator = CountingAllocator;
+/// ```
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn record_alloc(size: usize) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        let allocated = ALLOCATED_BYTES.fetch_add(size as u64, Ordering::Relaxed) + size as u64;
+        let live = allocated.saturating_sub(DEALLOCATED_BYTES.load(Ordering::Relaxed));
+        PEAK_LIVE_BYTES.fetch_max(live, Ordering::Relaxed);
+    }
+
+    fn record_dealloc(size: usize) {
+        DEALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        DEALLOCATED_BYTES.fetch_add(size as u64, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        Self::record_alloc(layout.size());
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        Self::record_alloc(layout.size());
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        Self::record_dealloc(layout.size());
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    /// A reallocation counts as a deallocation of the old size and an allocation of the new one.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        Self::record_dealloc(layout.size());
+        Self::record_alloc(new_size);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+/// A snapshot of the allocation counters of [`CountingAllocator`].
+/// All counters stay at 0 if the [`CountingAllocator`] is not installed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AllocationStats {
+    /// Number of allocations (including reallocations).
+    pub allocations: u64,
+    /// Number of deallocations (including reallocations).
+    pub deallocations: u64,
+    /// Number of bytes allocated.
+    pub allocated_bytes: u64,
+    /// Number of bytes deallocated.
+    pub deallocated_bytes: u64,
+    /// Number of bytes allocated and not yet deallocated.
+    pub live_bytes: u64,
+    /// Highest number of live bytes.
+    pub peak_live_bytes: u64,
+}
+
+impl AllocationStats {
+    pub fn current() -> Self {
+        let allocated_bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+        let deallocated_bytes = DEALLOCATED_BYTES.load(Ordering::Relaxed);
+
+        Self {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            deallocations: DEALLOCATIONS.load(Ordering::Relaxed),
+            allocated_bytes,
+            deallocated_bytes,
+            live_bytes: allocated_bytes.saturating_sub(deallocated_bytes),
+            peak_live_bytes: PEAK_LIVE_BYTES.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Resets the peak number of live bytes to the current number of live bytes, and returns
+    /// the current counters. Use this at the start of a measurement, so that the peak of
+    /// [`AllocationStats::since`] belongs to the measurement.
+    pub fn start() -> Self {
+        let current = Self::current();
+        PEAK_LIVE_BYTES.store(current.live_bytes, Ordering::Relaxed);
+
+        Self {
+            peak_live_bytes: current.live_bytes,
+            ..current
+        }
+    }
+
+    /// Returns whether the [`CountingAllocator`] is installed.
+    pub fn is_tracking() -> bool {
+        ALLOCATIONS.load(Ordering::Relaxed) > 0
+    }
+
+    /// Returns the allocations made since `earlier` was taken. The live and peak live bytes are
+    /// relative to the live bytes of `earlier`.
+    pub fn since(&self, earlier: &Self) -> Self {
+        Self {
+            allocations: self.allocations - earlier.allocations,
+            deallocations: self.deallocations - earlier.deallocations,
+            allocated_bytes: self.allocated_bytes - earlier.allocated_bytes,
+            deallocated_bytes: self.deallocated_bytes - earlier.deallocated_bytes,
+            live_bytes: self.live_bytes.saturating_sub(earlier.live_bytes),
+            peak_live_bytes: self.peak_live_bytes.saturating_sub(earlier.live_bytes),
+        }
+    }
+}
+
+/// The allocations made while processing a number of tasks.
+#[derive(Debug, Clone, Copy)]
+pub struct AllocationResult {
+    pub stats: AllocationStats,
+    pub tasks: u64,
+}
+
+impl AllocationResult {
+    /// Returns the number of allocations per task, or `None` if allocations are not tracked.
+    pub fn allocations_per_task(&self) -> Option<f64> {
+        AllocationStats::is_tracking().then(|| self.stats.allocations as f64 / self.tasks as f64)
+    }
+
+    /// Returns the number of bytes allocated per task, or `None` if allocations are not tracked.
+    pub fn bytes_per_task(&self) -> Option<f64> {
+        AllocationStats::is_tracking()
+            .then(|| self.stats.allocated_bytes as f64 / self.tasks as f64)
+    }
+
+    /// Returns the peak number of live bytes, or `None` if allocations are not tracked.
+    pub fn peak_live_bytes(&self) -> Option<u64> {
+        AllocationStats::is_tracking().then_some(self.stats.peak_live_bytes)
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    fmt, fs,
+    io::{self, BufReader, BufWriter},
+    path::Path,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use tabled::Tabled;
+
+use super::{
+    Report, format_duration, format_percentage,
+    stats::{self, mann_whitney_u},
+};
+
+/// The p-value below which a change is considered significant.
+pub const SIGNIFICANCE_LEVEL: f64 = 0.05;
+
+/// The unit of the samples of a [`BaselineCase`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Unit {
+    Nanos,
+    Fraction,
+}
+
+impl Unit {
+    fn format(self, value: f64) -> String {
+        match self {
+            Unit::Nanos => format_duration(&Duration::from_nanos(value as u64)),
+            Unit::Fraction => format_percentage(&value),
+        }
+    }
+}
+
+/// The samples of a single metric of a benchmark case. Lower is better for every metric.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaselineCase {
+    pub id: String,
+    pub runtime: String,
+    pub metric: String,
+    pub unit: Unit,
+    /// One sample per measured iteration.
+    pub samples: Vec<f64>,
+}
+
+impl BaselineCase {
+    fn mean(&self) -> f64 {
+        stats::mean(&self.samples)
+    }
+}
+
+/// A named snapshot of the results of a benchmark, to compare later runs against.
+///
+/// A baseline keeps one metric per throughput, latency and load case:
+/// - throughput: the duration of every iteration
+/// - latency: the median latency of every iteration
+/// - load: the load of the actor in every steady-state iteration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Baseline {
+    pub name: String,
+    /// Unix timestamp of the run, in seconds.
+    pub timestamp: u64,
+    pub git_commit: Option<String>,
+    pub tokio_version: Option<String>,
+    pub num_tasks: usize,
+    pub iterations: usize,
+    /// Duration of a single task, in nanoseconds.
+    pub task_duration: u64,
+    pub cases: Vec<BaselineCase>,
+}
+
+impl Baseline {
+    /// Extracts the baseline metrics from the cases of `report`.
+    pub fn from_report(name: &str, report: &Report) -> Self {
+        let metadata = &report.metadata;
+        let num_tasks = metadata.parameters.num_tasks;
+
+        let cases = report
+            .cases
+            .iter()
+            .filter_map(|case| {
+                let (metric, unit, samples) = match case.scenario.as_str() {
+                    "throughput" | "slow-consumer" => (
+                        "iteration duration",
+                        Unit::Nanos,
+                        case.measurements
+                            .iter()
+                            .map(|measurement| measurement.elapsed.as_nanos() as f64)
+                            .collect(),
+                    ),
+                    "latency" => (
+                        "median latency",
+                        Unit::Nanos,
+                        case.latency
+                            .iter()
+                            .flat_map(|latency| &latency.iterations)
+                            .map(|iteration| iteration.quantile(0.5).as_nanos() as f64)
+                            .collect(),
+                    ),
+                    "load" => (
+                        "load",
+                        Unit::Fraction,
+                        case.intervals
+                            .iter()
+                            .filter(|interval| !interval.warmup)
+                            .map(|interval| interval.load)
+                            .collect(),
+                    ),
+                    _ => return None,
+                };
+
+                Some(BaselineCase {
+                    id: case.id.clone(),
+                    runtime: case.runtime.clone(),
+                    metric: metric.to_string(),
+                    unit,
+                    samples,
+                })
+            })
+            .filter(|case| !case.samples.is_empty())
+            .collect();
+
+        Self {
+            name: name.to_string(),
+            timestamp: metadata.timestamp,
+            git_commit: metadata.system.git_commit.clone(),
+            tokio_version: metadata.system.tokio_version.clone(),
+            num_tasks,
+            iterations: metadata.parameters.iterations,
+            task_duration: metadata.parameters.task_duration.as_nanos() as u64,
+            cases,
+        }
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let file = BufReader::new(fs::File::open(path)?);
+        Ok(serde_json::from_reader(file)?)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let file = BufWriter::new(fs::File::create(path)?);
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+
+    /// Returns whether `other` was run with the same parameters, so its results are comparable.
+    pub fn is_comparable(&self, other: &Baseline) -> bool {
+        self.num_tasks == other.num_tasks
+            && self.iterations == other.iterations
+            && self.task_duration == other.task_duration
+    }
+
+    /// Compares the cases of `current` to the cases of this baseline with the same id and runtime.
+    ///
+    /// A case regresses if its mean got worse by more than `threshold` (a fraction), and the
+    /// change is significant according to a Mann-Whitney U test of the samples. Cases with a
+    /// single sample, i.e. runs with one iteration, can't be tested for significance, so only the
+    /// threshold is applied.
+    pub fn compare(&self, current: &Baseline, threshold: f64) -> Vec<ComparisonRow> {
+        current
+            .cases
+            .iter()
+            .filter_map(|case| {
+                let baseline = self
+                    .cases
+                    .iter()
+                    .find(|baseline| baseline.id == case.id && baseline.runtime == case.runtime)?;
+
+                let change = case.mean() / baseline.mean() - 1.0;
+                let p_value = (baseline.samples.len() > 1 && case.samples.len() > 1)
+                    .then(|| mann_whitney_u(&baseline.samples, &case.samples));
+                let significant = p_value.is_none_or(|p_value| p_value < SIGNIFICANCE_LEVEL);
+
+                let verdict = if significant && change > threshold {
+                    Verdict::Regressed
+                } else if significant && change < -threshold {
+                    Verdict::Improved
+                } else {
+                    Verdict::Unchanged
+                };
+
+                Some(ComparisonRow {
+                    id: case.id.clone(),
+                    runtime: case.runtime.clone(),
+                    metric: case.metric.clone(),
+                    baseline: baseline.unit.format(baseline.mean()),
+                    current: case.unit.format(case.mean()),
+                    change,
+                    p_value,
+                    verdict,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    Regressed,
+    Improved,
+    Unchanged,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Verdict::Regressed => "regressed",
+            Verdict::Improved => "improved",
+            Verdict::Unchanged => "unchanged",
+        })
+    }
+}
+
+#[derive(Debug, Tabled, Clone, Serialize)]
+pub struct ComparisonRow {
+    /// Id of the benchmark case.
+    pub id: String,
+    pub runtime: String,
+    /// The compared metric. Lower is better.
+    pub metric: String,
+    /// Mean of the metric in the baseline.
+    pub baseline: String,
+    /// Mean of the metric in this run.
+    pub current: String,
+    /// Relative change of the mean.
+    #[tabled(display = "format_change")]
+    pub change: f64,
+    /// p-value of the Mann-Whitney U test, if the metric has multiple samples.
+    #[tabled(display = "format_p_value")]
+    pub p_value: Option<f64>,
+    pub verdict: Verdict,
+}
+
+fn format_change(change: &f64) -> String {
+    format!("{:+.2}%", change * 100.0)
+}
+
+fn format_p_value(p_value: &Option<f64>) -> String {
+    p_value.map_or("-".to_string(), |p_value| format!("{p_value:.3}"))
+}
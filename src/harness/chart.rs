@@ -0,0 +1,395 @@
+use std::{fmt::Write, time::Duration};
+
+use super::{
+    Histogram, format_duration,
+    stats::{Summary, quantile},
+};
+
+const WIDTH: f64 = 800.0;
+const MARGIN: f64 = 20.0;
+const TITLE_HEIGHT: f64 = 40.0;
+/// Space below the plot for the tick labels of the x axis and its label.
+const AXIS_HEIGHT: f64 = 50.0;
+/// Height of a row of the box plot and bar chart.
+const ROW_HEIGHT: f64 = 32.0;
+const FONT_SIZE: f64 = 12.0;
+/// Approximate width of a character at [`FONT_SIZE`], to make room for the labels.
+const CHAR_WIDTH: f64 = 7.0;
+const TICKS: usize = 6;
+
+/// Colors of the series, from the Tableau 10 palette.
+const PALETTE: [&str; 10] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7",
+    "#9c755f", "#bab0ac",
+];
+
+/// A chart of the cases of a [`Report`](super::Report), as a standalone SVG.
+#[derive(Debug, Clone)]
+pub struct Chart {
+    /// Name of the file the chart is written to.
+    pub file_name: &'static str,
+    pub title: &'static str,
+    pub svg: String,
+}
+
+/// The largest percentile of [`latency_percentiles`], as the number of nines (99.999%).
+const MAX_NINES: f64 = 5.0;
+/// Number of points per line of [`latency_percentiles`].
+const PERCENTILE_POINTS: usize = 200;
+
+/// Plots the latency at every percentile of every histogram, with the percentiles on a
+/// logarithmic scale of nines, so that the tail is as visible as the median.
+pub fn latency_percentiles(title: &str, series: &[(String, &Histogram)]) -> String {
+    let height = 450.0;
+    let legend_width = legend_width(series.iter().map(|(label, _)| label.as_str()));
+    let plot = Plot {
+        left: MARGIN + 70.0,
+        right: WIDTH - MARGIN - legend_width,
+        top: TITLE_HEIGHT,
+        bottom: height - AXIS_HEIGHT,
+    };
+
+    // Only plot the percentiles that the smallest histogram has enough samples for.
+    let samples = series
+        .iter()
+        .map(|(_, histogram)| histogram.len())
+        .min()
+        .unwrap_or(0);
+    let nines = (samples.max(1) as f64)
+        .log10()
+        .floor()
+        .clamp(1.0, MAX_NINES);
+    let max_latency = series
+        .iter()
+        .map(|(_, histogram)| histogram.quantile(1.0 - 10f64.powf(-nines)))
+        .max()
+        .unwrap_or_default();
+    let y_ticks = linear_ticks(0.0, max_latency.as_nanos().max(1) as f64);
+    let y_max = *y_ticks.last().unwrap();
+
+    let mut svg = Svg::new(height, title);
+    for nine in 0..=nines as usize {
+        let x = plot.x(nine as f64 / nines);
+        svg.grid_line(x, plot.top, x, plot.bottom);
+        svg.text(x, plot.bottom + 18.0, "middle", &percentile_label(nine));
+    }
+    for &tick in &y_ticks {
+        let y = plot.y(tick / y_max);
+        svg.grid_line(plot.left, y, plot.right, y);
+        let label = format_duration(&Duration::from_nanos(tick as u64));
+        svg.text(plot.left - 6.0, y + 4.0, "end", &label);
+    }
+    svg.axes(&plot, "Percentile", "Latency");
+
+    for (i, (label, histogram)) in series.iter().enumerate() {
+        let points = (0..=PERCENTILE_POINTS)
+            .map(|point| {
+                let fraction = point as f64 / PERCENTILE_POINTS as f64;
+                let latency = histogram.quantile(1.0 - 10f64.powf(-fraction * nines));
+                let y = plot.y((latency.as_nanos() as f64 / y_max).min(1.0));
+                format!("{:.1},{:.1}", plot.x(fraction), y)
+            })
+            .collect::<Vec<_>>();
+        let _ = write!(
+            svg.content,
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="2"/>"#,
+            points.join(" "),
+            color(i)
+        );
+        svg.legend(
+            plot.right + 16.0,
+            plot.top + i as f64 * 20.0,
+            color(i),
+            label,
+        );
+    }
+
+    svg.finish()
+}
+
+/// Draws a horizontal box plot of the values of every series: the box spans the quartiles, the
+/// whiskers the values within 1.5 interquartile ranges of them, and the dots are the outliers.
+pub fn box_plot(
+    title: &str,
+    axis_label: &str,
+    series: &[(String, Vec<f64>)],
+    format: impl Fn(&f64) -> String,
+) -> String {
+    let height = TITLE_HEIGHT + series.len() as f64 * ROW_HEIGHT + AXIS_HEIGHT;
+    let plot = Plot {
+        left: MARGIN + legend_width(series.iter().map(|(label, _)| label.as_str())),
+        right: WIDTH - MARGIN,
+        top: TITLE_HEIGHT,
+        bottom: height - AXIS_HEIGHT,
+    };
+
+    let all = series
+        .iter()
+        .flat_map(|(_, values)| values.iter().copied())
+        .collect::<Summary>();
+    let (min, max) = (all.min().unwrap_or(0.0), all.max().unwrap_or(1.0));
+    let padding = ((max - min) * 0.05)
+        .max(max.abs() * 0.01)
+        .max(f64::MIN_POSITIVE);
+    let ticks = linear_ticks(min - padding, max + padding);
+    let (x_min, x_max) = (ticks[0], *ticks.last().unwrap());
+    let x = |value: f64| plot.x((value - x_min) / (x_max - x_min));
+
+    let mut svg = Svg::new(height, title);
+    for &tick in &ticks {
+        svg.grid_line(x(tick), plot.top, x(tick), plot.bottom);
+        svg.text(x(tick), plot.bottom + 18.0, "middle", &format(&tick));
+    }
+    svg.axes(&plot, axis_label, "");
+
+    for (i, (label, values)) in series.iter().enumerate() {
+        let top = plot.top + i as f64 * ROW_HEIGHT;
+        let middle = top + ROW_HEIGHT / 2.0;
+        svg.text(plot.left - 8.0, middle + 4.0, "end", label);
+        if values.is_empty() {
+            continue;
+        }
+
+        let mut sorted = values.clone();
+        sorted.sort_by(f64::total_cmp);
+        let (q1, median, q3) = (
+            quantile(&sorted, 0.25),
+            quantile(&sorted, 0.5),
+            quantile(&sorted, 0.75),
+        );
+        let iqr = q3 - q1;
+        let (low, high) = (q1 - 1.5 * iqr, q3 + 1.5 * iqr);
+        // The quartiles are interpolated, so the closest values inside the fences can be inside
+        // the box.
+        let whisker_low = sorted
+            .iter()
+            .copied()
+            .find(|v| *v >= low)
+            .map_or(q1, |v| v.min(q1));
+        let whisker_high = (sorted.iter().copied())
+            .rfind(|v| *v <= high)
+            .map_or(q3, |v| v.max(q3));
+
+        let (box_top, box_bottom) = (top + 6.0, top + ROW_HEIGHT - 6.0);
+        svg.line(x(whisker_low), middle, x(q1), middle, "#333", 1.0);
+        svg.line(x(q3), middle, x(whisker_high), middle, "#333", 1.0);
+        for whisker in [whisker_low, whisker_high] {
+            svg.line(
+                x(whisker),
+                box_top + 4.0,
+                x(whisker),
+                box_bottom - 4.0,
+                "#333",
+                1.0,
+            );
+        }
+        let _ = write!(
+            svg.content,
+            r##"<rect x="{:.1}" y="{box_top:.1}" width="{:.1}" height="{:.1}" fill="{}" fill-opacity="0.6" stroke="#333"/>"##,
+            x(q1),
+            (x(q3) - x(q1)).max(1.0),
+            box_bottom - box_top,
+            color(i)
+        );
+        svg.line(x(median), box_top, x(median), box_bottom, "#333", 2.0);
+
+        for outlier in sorted.iter().filter(|v| **v < low || **v > high) {
+            let _ = write!(
+                svg.content,
+                r##"<circle cx="{:.1}" cy="{middle:.1}" r="2.5" fill="none" stroke="#333"/>"##,
+                x(*outlier)
+            );
+        }
+    }
+
+    svg.finish()
+}
+
+/// Draws a horizontal bar of the value of every series, starting at zero.
+pub fn bar_chart(
+    title: &str,
+    axis_label: &str,
+    series: &[(String, f64)],
+    format: impl Fn(&f64) -> String,
+) -> String {
+    let height = TITLE_HEIGHT + series.len() as f64 * ROW_HEIGHT + AXIS_HEIGHT;
+    let plot = Plot {
+        left: MARGIN + legend_width(series.iter().map(|(label, _)| label.as_str())),
+        // Room for the value at the end of the longest bar.
+        right: WIDTH - MARGIN - 60.0,
+        top: TITLE_HEIGHT,
+        bottom: height - AXIS_HEIGHT,
+    };
+
+    let max = series
+        .iter()
+        .map(|(_, value)| *value)
+        .fold(0.0, f64::max)
+        .max(f64::MIN_POSITIVE);
+    let ticks = linear_ticks(0.0, max);
+    let x_max = *ticks.last().unwrap();
+    let x = |value: f64| plot.x(value / x_max);
+
+    let mut svg = Svg::new(height, title);
+    for &tick in &ticks {
+        svg.grid_line(x(tick), plot.top, x(tick), plot.bottom);
+        svg.text(x(tick), plot.bottom + 18.0, "middle", &format(&tick));
+    }
+    svg.axes(&plot, axis_label, "");
+
+    for (i, (label, value)) in series.iter().enumerate() {
+        let top = plot.top + i as f64 * ROW_HEIGHT;
+        let middle = top + ROW_HEIGHT / 2.0;
+        svg.text(plot.left - 8.0, middle + 4.0, "end", label);
+        let _ = write!(
+            svg.content,
+            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"/>"#,
+            plot.left,
+            top + 6.0,
+            x(*value) - plot.left,
+            ROW_HEIGHT - 12.0,
+            color(i)
+        );
+        svg.text(x(*value) + 6.0, middle + 4.0, "start", &format(value));
+    }
+
+    svg.finish()
+}
+
+/// The area of a chart that the data is drawn in.
+struct Plot {
+    left: f64,
+    right: f64,
+    top: f64,
+    bottom: f64,
+}
+
+impl Plot {
+    /// Returns the x coordinate of a fraction of the width of the plot.
+    fn x(&self, fraction: f64) -> f64 {
+        self.left + fraction * (self.right - self.left)
+    }
+
+    /// Returns the y coordinate of a fraction of the height of the plot, from the bottom.
+    fn y(&self, fraction: f64) -> f64 {
+        self.bottom - fraction * (self.bottom - self.top)
+    }
+}
+
+struct Svg {
+    content: String,
+}
+
+impl Svg {
+    fn new(height: f64, title: &str) -> Self {
+        let mut content = String::new();
+        let _ = write!(
+            content,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{height}" viewBox="0 0 {WIDTH} {height}" font-family="sans-serif" font-size="{FONT_SIZE}">"#
+        );
+        let _ = write!(
+            content,
+            r#"<rect width="100%" height="100%" fill="white"/><text x="{}" y="24" text-anchor="middle" font-size="16">{}</text>"#,
+            WIDTH / 2.0,
+            escape(title)
+        );
+        Self { content }
+    }
+
+    fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, stroke: &str, width: f64) {
+        let _ = write!(
+            self.content,
+            r#"<line x1="{x1:.1}" y1="{y1:.1}" x2="{x2:.1}" y2="{y2:.1}" stroke="{stroke}" stroke-width="{width}"/>"#
+        );
+    }
+
+    fn grid_line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64) {
+        self.line(x1, y1, x2, y2, "#e0e0e0", 1.0);
+    }
+
+    fn text(&mut self, x: f64, y: f64, anchor: &str, text: &str) {
+        let _ = write!(
+            self.content,
+            r#"<text x="{x:.1}" y="{y:.1}" text-anchor="{anchor}">{}</text>"#,
+            escape(text)
+        );
+    }
+
+    /// Draws the x and y axes of `plot`, with their labels.
+    fn axes(&mut self, plot: &Plot, x_label: &str, y_label: &str) {
+        self.line(plot.left, plot.bottom, plot.right, plot.bottom, "#333", 1.0);
+        self.line(plot.left, plot.top, plot.left, plot.bottom, "#333", 1.0);
+        self.text(
+            (plot.left + plot.right) / 2.0,
+            plot.bottom + 40.0,
+            "middle",
+            x_label,
+        );
+        if !y_label.is_empty() {
+            let (x, y) = (MARGIN, (plot.top + plot.bottom) / 2.0);
+            let _ = write!(
+                self.content,
+                r#"<text x="{x:.1}" y="{y:.1}" text-anchor="middle" transform="rotate(-90 {x:.1} {y:.1})">{}</text>"#,
+                escape(y_label)
+            );
+        }
+    }
+
+    fn legend(&mut self, x: f64, y: f64, color: &str, label: &str) {
+        let _ = write!(
+            self.content,
+            r#"<rect x="{x:.1}" y="{:.1}" width="12" height="12" fill="{color}"/>"#,
+            y - 10.0
+        );
+        self.text(x + 18.0, y, "start", label);
+    }
+
+    fn finish(mut self) -> String {
+        self.content.push_str("</svg>\n");
+        self.content
+    }
+}
+
+/// Returns the label of the percentile with `nines` nines, e.g. `99.9%` for 3.
+fn percentile_label(nines: usize) -> String {
+    match nines {
+        0 => "0%".to_string(),
+        1 => "90%".to_string(),
+        _ => format!("99.{}%", "9".repeat(nines - 2)).replace(".%", "%"),
+    }
+}
+
+fn color(i: usize) -> &'static str {
+    PALETTE[i % PALETTE.len()]
+}
+
+/// Returns the width of a column of labels, or of the legend.
+fn legend_width<'a>(labels: impl Iterator<Item = &'a str>) -> f64 {
+    let longest = labels.map(|label| label.chars().count()).max().unwrap_or(0);
+    longest as f64 * CHAR_WIDTH + 30.0
+}
+
+/// Returns about [`TICKS`] evenly spaced round values, from at most `min` to at least `max`.
+fn linear_ticks(min: f64, max: f64) -> Vec<f64> {
+    let range = (max - min).max(f64::MIN_POSITIVE);
+    let rough_step = range / TICKS as f64;
+    let magnitude = 10f64.powf(rough_step.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= rough_step)
+        .unwrap_or(10.0 * magnitude);
+
+    let first = (min / step).floor() as i64;
+    let last = (max / step).ceil() as i64;
+    (first..=last.max(first + 1))
+        .map(|tick| tick as f64 * step)
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
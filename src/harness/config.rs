@@ -0,0 +1,423 @@
+use std::{
+    fmt,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use serde::Serialize;
+use tabled::Table;
+
+use crate::_01::{DEFAULT_TASK_DURATION, set_task_duration};
+
+use super::{
+    Arrival, DEFAULT_RATES, OutlierMethod, RuntimeConfig, Scenario, TableStyle, export::as_nanos,
+};
+
+const USAGE: &str = "\
+Usage: cargo bench --bench <BENCH> -- [OPTIONS] [FILTER]...
+
+Arguments:
+  [FILTER]...                  Only run cases whose id (<bench>/<actor>/<scenario>) contains
+                               any of the filters
+
+Options:
+  --tasks <N>                  Number of tasks per iteration
+  --iterations <N>             Number of iterations
+  --warmup <N>                 Number of warm-up iterations to run and discard before the
+                               measured iterations [default: 1]
+  --outliers <METHOD>          How iterations are classified as outliers (tukey, mad)
+                               [default: tukey]
+  --task-duration <DURATION>   Duration of a single task, e.g. 500ns, 10us, 1ms
+  --capacity <N>               Capacity of the task channel [default: tasks]
+  --scenario <SCENARIO>        Only run this scenario (throughput, latency, load, open-loop,
+                               slow-consumer), can be repeated [default: throughput, latency,
+                               load]
+  --result-capacity <N>        Capacity of the results channel in the slow-consumer scenario
+                               [default: 16]
+  --consumer-delay <DURATION>  Time the consumer spends on every result in the slow-consumer
+                               scenario, e.g. 2us [default: 2us]
+  --rate <RATE>                Offered load of the open-loop scenario in tasks per second,
+                               e.g. 50k, can be repeated [default: 10k, 25k, 50k, 100k, 200k]
+  --arrival <ARRIVAL>          Arrival pattern of the open-loop scenario (fixed, poisson,
+                               bursty, bursty:<SIZE>) [default: poisson]
+  --runtime <RUNTIME>          Run on this runtime configuration, can be repeated, e.g.
+                               current-thread, multi-thread:4,no-lifo,event-interval=31,
+                               global-queue-interval=61,separate-producer, or matrix for a
+                               preset matrix [default: depends on the benchmark]
+  --runtime-metrics            Also collect metrics of the whole runtime in the load scenario
+                               (requires --cfg tokio_unstable)
+  --sweep                      Also run the variants of the actors that sweep their parameters,
+                               e.g. the poll orders, budgets and batch sizes of 01
+  --results-dir <DIR>          Directory to write JSON and CSV results to [default: results]
+  --no-export                  Don't write results to disk
+  --table-style <STYLE>        Border style of the tables (modern, markdown, ascii, none)
+                               [default: modern]
+  --no-color                   Don't highlight rows of the tables with colors [default: no
+                               colors if stdout is not a terminal or NO_COLOR is set]
+  --update-readme              Regenerate the results section of the README of the benchmark
+  --save-baseline <NAME>       Save the results as a named baseline
+  --baseline <NAME>            Compare the results to a saved baseline, and exit with an error
+                               if any case regressed
+  --threshold <PERCENT>        Change from which a significant difference to the baseline is
+                               a regression [default: 5%]
+  -h, --help                   Print help";
+
+/// The configuration of a benchmark run, parsed from the command line.
+///
+/// Arguments are passed after `--`, e.g. `cargo bench --bench 01 -- --tasks 1000 Future`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Config {
+    /// Name of the benchmark, used as the first part of every case id.
+    pub bench: &'static str,
+    /// Number of tasks per iteration.
+    pub num_tasks: usize,
+    /// Number of iterations.
+    pub iterations: usize,
+    /// Number of warm-up iterations that are run and discarded before the measured iterations.
+    pub warmup_iterations: usize,
+    /// How iterations are classified as outliers.
+    pub outlier_method: OutlierMethod,
+    /// Duration of a single task.
+    #[serde(serialize_with = "as_nanos")]
+    pub task_duration: Duration,
+    /// Capacity of the task channel. Defaults to `num_tasks`.
+    ///
+    /// The results channel has room for all tasks of an iteration, except in the slow-consumer
+    /// scenario.
+    pub channel_capacity: Option<usize>,
+    /// Capacity of the results channel in the slow-consumer scenario.
+    pub result_capacity: usize,
+    /// Time the consumer spends on every result in the slow-consumer scenario.
+    #[serde(serialize_with = "as_nanos")]
+    pub consumer_delay: Duration,
+    /// The scenarios to run.
+    pub scenarios: Vec<Scenario>,
+    /// The runtime configurations to run every scenario on.
+    pub runtimes: Vec<RuntimeConfig>,
+    /// Offered loads of the open-loop scenario, in tasks per second.
+    pub rates: Vec<f64>,
+    /// Arrival pattern of the open-loop scenario.
+    pub arrival: Arrival,
+    /// Whether the load scenario also collects metrics of the whole runtime.
+    pub runtime_metrics: bool,
+    /// Whether to also run the parameter sweeps of the benchmark.
+    pub sweep: bool,
+    /// Case id filters. If empty, all cases are run.
+    pub filters: Vec<String>,
+    /// Border style of the printed tables.
+    pub table_style: TableStyle,
+    /// Whether to highlight rows of the printed tables with colors.
+    pub color: bool,
+    /// Whether to regenerate the results section of the README of the benchmark.
+    pub update_readme: bool,
+    /// The command line arguments the configuration was parsed from, to reproduce the run.
+    pub args: Vec<String>,
+    /// Directory to write results to. Results are not written if this is `None`.
+    #[serde(skip)]
+    pub results_dir: Option<PathBuf>,
+    /// Name of the baseline to save the results as.
+    pub save_baseline: Option<String>,
+    /// Name of the baseline to compare the results to.
+    pub baseline: Option<String>,
+    /// Relative change from which a significant difference to the baseline is a regression.
+    pub regression_threshold: f64,
+}
+
+impl Config {
+    pub fn new(bench: &'static str) -> Self {
+        Self {
+            bench,
+            num_tasks: 50000,
+            iterations: 100,
+            warmup_iterations: 1,
+            outlier_method: OutlierMethod::Tukey,
+            task_duration: DEFAULT_TASK_DURATION,
+            channel_capacity: None,
+            result_capacity: 16,
+            consumer_delay: Duration::from_micros(2),
+            scenarios: Scenario::DEFAULT.to_vec(),
+            runtimes: vec![RuntimeConfig::current_thread()],
+            rates: DEFAULT_RATES.to_vec(),
+            arrival: Arrival::Poisson,
+            runtime_metrics: false,
+            sweep: false,
+            filters: Vec::new(),
+            table_style: TableStyle::Modern,
+            color: std::io::stdout().is_terminal()
+                && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()),
+            update_readme: false,
+            args: Vec::new(),
+            results_dir: Some(PathBuf::from("results")),
+            save_baseline: None,
+            baseline: None,
+            regression_threshold: 0.05,
+        }
+    }
+
+    /// Applies the command line arguments of this process, and sets the task duration.
+    /// Prints the usage and exits on `--help` or invalid arguments.
+    pub fn with_env_args(self) -> Self {
+        let config = match self
+            .parse_args(std::env::args().skip(1))
+            .and_then(Config::check_baseline)
+        {
+            Ok(config) => config,
+            Err(err) => {
+                eprintln!("error: {err}\n\n{USAGE}");
+                std::process::exit(2);
+            }
+        };
+
+        set_task_duration(config.task_duration);
+        config
+    }
+
+    /// Applies `args` to this configuration.
+    pub fn parse_args(mut self, args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let args = args.into_iter().collect::<Vec<_>>();
+        self.args = args
+            .iter()
+            .filter(|arg| *arg != "--bench")
+            .cloned()
+            .collect();
+
+        let mut args = args.into_iter();
+        let mut scenarios = Vec::new();
+        let mut rates = Vec::new();
+        let mut runtimes = Vec::new();
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("missing value for `{arg}`"));
+
+            match arg.as_str() {
+                // Passed by `cargo bench`.
+                "--bench" => {}
+                "-h" | "--help" => {
+                    println!("{USAGE}");
+                    std::process::exit(0);
+                }
+                "--tasks" => self.num_tasks = parse_positive(&value()?)?,
+                "--iterations" => self.iterations = parse_positive(&value()?)?,
+                "--warmup" => self.warmup_iterations = parse_number(&value()?)?,
+                "--outliers" => self.outlier_method = OutlierMethod::parse(&value()?)?,
+                "--task-duration" => self.task_duration = parse_duration(&value()?)?,
+                "--capacity" => self.channel_capacity = Some(parse_positive(&value()?)?),
+                "--scenario" => scenarios.push(parse_scenario(&value()?)?),
+                "--result-capacity" => self.result_capacity = parse_positive(&value()?)?,
+                "--consumer-delay" => self.consumer_delay = parse_duration(&value()?)?,
+                "--rate" => rates.push(parse_rate(&value()?)?),
+                "--runtime" => match value()?.as_str() {
+                    "matrix" => runtimes.extend(RuntimeConfig::matrix()),
+                    runtime => runtimes.push(RuntimeConfig::parse(runtime)?),
+                },
+                "--arrival" => self.arrival = Arrival::parse(&value()?)?,
+                "--runtime-metrics" => {
+                    if !cfg!(tokio_unstable) {
+                        return Err(
+                            "`--runtime-metrics` requires `--cfg tokio_unstable`".to_string()
+                        );
+                    }
+                    self.runtime_metrics = true;
+                }
+                "--sweep" => self.sweep = true,
+                "--results-dir" => self.results_dir = Some(PathBuf::from(value()?)),
+                "--no-export" => self.results_dir = None,
+                "--table-style" => self.table_style = TableStyle::parse(&value()?)?,
+                "--no-color" => self.color = false,
+                "--update-readme" => self.update_readme = true,
+                "--save-baseline" => self.save_baseline = Some(value()?),
+                "--baseline" => self.baseline = Some(value()?),
+                "--threshold" => self.regression_threshold = parse_percentage(&value()?)?,
+                flag if flag.starts_with('-') => return Err(format!("unknown option `{flag}`")),
+                filter => self.filters.push(filter.to_string()),
+            }
+        }
+
+        if !scenarios.is_empty() {
+            self.scenarios = scenarios;
+        }
+        if !rates.is_empty() {
+            self.rates = rates;
+        }
+        if !runtimes.is_empty() {
+            self.runtimes = runtimes;
+        }
+
+        Ok(self)
+    }
+
+    /// Fails if the baseline to compare to doesn't exist, so it is noticed before running the
+    /// benchmark.
+    fn check_baseline(self) -> Result<Self, String> {
+        if let Some(name) = &self.baseline {
+            let path = self.baseline_path(name);
+            if !path.exists() {
+                return Err(format!("baseline `{name}` not found at {}", path.display()));
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Returns the path of the baseline called `name`. Baselines are stored in the results
+    /// directory, even if exporting the results is disabled.
+    pub fn baseline_path(&self, name: &str) -> PathBuf {
+        self.results_dir
+            .as_deref()
+            .unwrap_or(Path::new("results"))
+            .join(self.bench)
+            .join("baselines")
+            .join(format!("{name}.json"))
+    }
+
+    /// Prints `table` in the configured style.
+    pub fn print_table(&self, table: &mut Table) {
+        println!("{}", self.table_style.render(table, self.color));
+    }
+
+    /// Returns the command that reproduces this run.
+    pub fn command(&self) -> String {
+        let mut command = format!("cargo bench --bench {}", self.bench);
+        if !self.args.is_empty() {
+            command.push_str(" --");
+            for arg in &self.args {
+                command.push(' ');
+                command.push_str(&quote(arg));
+            }
+        }
+        command
+    }
+
+    /// Returns the capacity of the task channel.
+    pub fn channel_capacity(&self) -> usize {
+        self.channel_capacity.unwrap_or(self.num_tasks)
+    }
+
+    /// Returns the id of a benchmark case.
+    pub fn case_id(&self, actor: &str, scenario: Scenario) -> String {
+        format!("{}/{}/{}", self.bench, actor, scenario.name())
+    }
+
+    /// Returns whether the case of `actor` running `scenario` is selected.
+    pub fn is_selected(&self, actor: &str, scenario: Scenario) -> bool {
+        if !self.scenarios.contains(&scenario) {
+            return false;
+        }
+
+        self.matches(&self.case_id(actor, scenario))
+    }
+
+    /// Returns whether `id` matches any of the filters.
+    pub fn matches(&self, id: &str) -> bool {
+        self.filters.is_empty() || self.filters.iter().any(|filter| id.contains(filter))
+    }
+}
+
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Tasks: {}, Iterations: {} (+{} warm-up), Task duration: {:?}, Channel capacity: {}",
+            self.num_tasks,
+            self.iterations,
+            self.warmup_iterations,
+            self.task_duration,
+            self.channel_capacity()
+        )?;
+
+        if self.scenarios.contains(&Scenario::SlowConsumer) {
+            write!(
+                f,
+                ", Result capacity: {}, Consumer delay: {:?}",
+                self.result_capacity, self.consumer_delay
+            )?;
+        }
+
+        for runtime in &self.runtimes {
+            write!(f, "\nRuntime: {runtime}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Quotes `arg` for a POSIX shell, if it contains anything but plain characters.
+fn quote(arg: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "-_./:=,%".contains(c);
+    if !arg.is_empty() && arg.chars().all(plain) {
+        return arg.to_string();
+    }
+
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+fn parse_number(value: &str) -> Result<usize, String> {
+    value
+        .replace('_', "")
+        .parse()
+        .map_err(|_| format!("invalid number `{value}`"))
+}
+
+/// Parses a number that must be at least 1, e.g. a channel capacity, which `mpsc::channel`
+/// doesn't allow to be zero.
+pub(crate) fn parse_positive<T: FromStr + Default + PartialOrd>(value: &str) -> Result<T, String> {
+    match value.replace('_', "").parse() {
+        Ok(number) if number > T::default() => Ok(number),
+        Ok(_) => Err(format!("invalid number `{value}`, must be at least 1")),
+        Err(_) => Err(format!("invalid number `{value}`")),
+    }
+}
+
+/// Parses a rate like `500`, `50k` or `1.5M`.
+fn parse_rate(value: &str) -> Result<f64, String> {
+    let (number, multiplier) = match value.strip_suffix(['k', 'K']) {
+        Some(number) => (number, 1e3),
+        None => match value.strip_suffix('M') {
+            Some(number) => (number, 1e6),
+            None => (value, 1.0),
+        },
+    };
+
+    match number.replace('_', "").parse::<f64>() {
+        Ok(rate) if rate > 0.0 => Ok(rate * multiplier),
+        _ => Err(format!("invalid rate `{value}`")),
+    }
+}
+
+/// Parses a non-negative percentage like `5` or `2.5%` into a fraction.
+fn parse_percentage(value: &str) -> Result<f64, String> {
+    match value.trim_end_matches('%').parse::<f64>() {
+        Ok(percentage) if percentage >= 0.0 && percentage.is_finite() => Ok(percentage / 100.0),
+        _ => Err(format!("invalid percentage `{value}`")),
+    }
+}
+
+fn parse_scenario(value: &str) -> Result<Scenario, String> {
+    Scenario::ALL
+        .into_iter()
+        .find(|scenario| scenario.name() == value)
+        .ok_or(format!("unknown scenario `{value}`"))
+}
+
+/// Parses a duration like `500ns`, `10us`, `1.5ms` or `2s`.
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid duration `{value}`");
+
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .ok_or_else(invalid)?;
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().map_err(|_| invalid())?;
+
+    let nanos = match unit {
+        "ns" => number,
+        "us" | "µs" => number * 1e3,
+        "ms" => number * 1e6,
+        "s" => number * 1e9,
+        _ => return Err(invalid()),
+    };
+
+    Ok(Duration::from_nanos(nanos as u64))
+}
@@ -0,0 +1,510 @@
+use std::{
+    fs,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use tokio_metrics::TaskMetrics;
+
+use tabled::{
+    Table,
+    settings::{Color, object::Rows},
+};
+
+use super::{
+    Baseline, Config, LatencyResult, OutlierMethod, ResourceUsage, ScenarioResult, SystemInfo,
+    ThroughputMeasurement, ToRow, Verdict,
+    chart::{self, Chart},
+    format_percentage, format_throughput,
+    readme::{self, ReportTable},
+    table::calculate_load,
+};
+
+/// Serializes a [`Duration`] as an integer number of nanoseconds.
+pub fn as_nanos<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_nanos() as u64)
+}
+
+/// Serializes an optional [`Duration`] as an integer number of nanoseconds, or `null`.
+pub fn as_optional_nanos<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => as_nanos(duration, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Describes the benchmark run that produced a [`Report`].
+#[derive(Debug, Clone, Serialize)]
+pub struct Metadata {
+    /// Unix timestamp of the start of the run, in seconds.
+    pub timestamp: u64,
+    pub system: SystemInfo,
+    pub parameters: Config,
+}
+
+/// A single benchmark case in a [`Report`].
+#[derive(Debug, Serialize)]
+pub struct Case {
+    pub id: String,
+    pub runtime: String,
+    pub actor: String,
+    pub scenario: String,
+    /// The summary row, as printed in the tables.
+    pub summary: Value,
+    /// Per-iteration measurements of the throughput scenario.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub measurements: Vec<ThroughputMeasurement>,
+    /// Per-iteration metrics of the actor task in the load scenario.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub intervals: Vec<TaskInterval>,
+    /// Per-iteration resource usage of the process in the load scenario.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub resources: Vec<ResourceUsage>,
+    /// Latency histograms of the latency scenario. These are only written to CSV, because they
+    /// would make the JSON file huge.
+    #[serde(skip)]
+    pub latency: Option<LatencyResult>,
+}
+
+/// The metrics of the actor task during one iteration of the load scenario.
+#[derive(Debug, Serialize)]
+pub struct TaskInterval {
+    /// Whether the iteration is excluded from the steady state.
+    pub warmup: bool,
+    /// Load of the actor task during the iteration.
+    pub load: f64,
+    pub poll_count: u64,
+    pub slow_poll_count: u64,
+    #[serde(serialize_with = "as_nanos")]
+    pub mean_poll_duration: Duration,
+    pub slow_poll_ratio: f64,
+    #[serde(serialize_with = "as_nanos")]
+    pub mean_idle_duration: Duration,
+    #[serde(serialize_with = "as_nanos")]
+    pub mean_scheduled_duration: Duration,
+}
+
+impl TaskInterval {
+    fn new(metrics: &TaskMetrics, warmup: bool) -> Self {
+        Self {
+            warmup,
+            load: calculate_load(metrics),
+            poll_count: metrics.total_poll_count,
+            slow_poll_count: metrics.total_slow_poll_count,
+            mean_poll_duration: metrics.mean_poll_duration(),
+            slow_poll_ratio: metrics.slow_poll_ratio(),
+            mean_idle_duration: metrics.mean_idle_duration(),
+            mean_scheduled_duration: metrics.mean_scheduled_duration(),
+        }
+    }
+}
+
+/// Collects the results of a benchmark run, and writes them to JSON and CSV files in
+/// `<results_dir>/<bench>/`:
+/// - `results.json`: metadata, and the summary and measurements of every case
+/// - `<scenario>.csv`: the summary rows of every case of a scenario
+/// - `throughput_measurements.csv`: per-iteration throughput measurements
+/// - `latency_histogram.csv`: the latency histogram of every case
+/// - `latency_percentiles.svg`, `throughput.svg`, `load.svg`: charts of the cases
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub metadata: Metadata,
+    pub cases: Vec<Case>,
+    /// The tables that compare the cases, as published in the README with `--update-readme`.
+    #[serde(skip)]
+    pub tables: Vec<ReportTable>,
+}
+
+impl Report {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            metadata: Metadata {
+                timestamp: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+                system: SystemInfo::collect(),
+                parameters: config.clone(),
+            },
+            cases: Vec::new(),
+            tables: Vec::new(),
+        }
+    }
+
+    /// Adds a table that compares cases, to publish in the README.
+    pub fn add_table(&mut self, title: impl Into<String>, table: &Table) {
+        self.tables.push(ReportTable::new(title, table));
+    }
+
+    /// Adds the result of running a scenario for `actor`.
+    ///
+    /// Every offered load of the open-loop scenario is added as a separate case, with the rate
+    /// appended to the case id.
+    pub fn add(&mut self, runtime: &str, actor: &'static str, result: ScenarioResult) {
+        let mut resources = Vec::new();
+        let mut intervals = Vec::new();
+        let mut runtime_row = None;
+        let (scenario, summary, measurements, latency) = match result {
+            ScenarioResult::OpenLoop(result) => {
+                let rows = result.to_row(actor);
+                for (row, point) in rows.iter().zip(result.points) {
+                    self.cases.push(Case {
+                        id: format!("{}/{}", self.case_id(actor, "open-loop"), row.offered_rate),
+                        runtime: runtime.to_string(),
+                        actor: actor.to_string(),
+                        scenario: "open-loop".to_string(),
+                        summary: to_value(row),
+                        measurements: Vec::new(),
+                        intervals: Vec::new(),
+                        resources: Vec::new(),
+                        latency: Some(LatencyResult::new(
+                            vec![point.latency],
+                            OutlierMethod::default(),
+                        )),
+                    });
+                }
+                return;
+            }
+            ScenarioResult::Throughput(result) => (
+                "throughput",
+                to_value(&result.to_row(actor)),
+                result.measurements,
+                None,
+            ),
+            ScenarioResult::SlowConsumer(result) => (
+                "slow-consumer",
+                to_value(&result.to_row(actor)),
+                result.measurements,
+                None,
+            ),
+            ScenarioResult::Latency(result) => (
+                "latency",
+                to_value(&result.to_row(actor)),
+                Vec::new(),
+                Some(result),
+            ),
+            ScenarioResult::Load(result) => {
+                runtime_row = result.runtime.as_ref().map(|result| result.to_row(actor));
+                let summary = to_value(&result.to_row(actor));
+                resources = result.resources;
+                intervals = result
+                    .intervals
+                    .iter()
+                    .enumerate()
+                    .map(|(i, metrics)| TaskInterval::new(metrics, i < result.warmup))
+                    .collect();
+                ("load", summary, Vec::new(), None)
+            }
+        };
+
+        self.cases.push(Case {
+            id: self.case_id(actor, scenario),
+            runtime: runtime.to_string(),
+            actor: actor.to_string(),
+            scenario: scenario.to_string(),
+            summary,
+            measurements,
+            intervals,
+            resources,
+            latency,
+        });
+
+        // The runtime metrics of the load scenario are added as a separate case.
+        if let Some(row) = runtime_row {
+            self.add_row(runtime, actor, "runtime", &row);
+        }
+    }
+
+    /// Adds a custom summary row, for benchmarks that don't use the standard scenarios.
+    pub fn add_row(&mut self, runtime: &str, actor: &str, scenario: &str, row: &impl Serialize) {
+        self.cases.push(Case {
+            id: self.case_id(actor, scenario),
+            runtime: runtime.to_string(),
+            actor: actor.to_string(),
+            scenario: scenario.to_string(),
+            summary: to_value(row),
+            measurements: Vec::new(),
+            intervals: Vec::new(),
+            resources: Vec::new(),
+            latency: None,
+        });
+    }
+
+    fn case_id(&self, actor: &str, scenario: &str) -> String {
+        format!("{}/{}/{}", self.metadata.parameters.bench, actor, scenario)
+    }
+
+    /// Returns the directory the report is written to, if exporting is enabled.
+    pub fn dir(&self) -> Option<PathBuf> {
+        let parameters = &self.metadata.parameters;
+        parameters
+            .results_dir
+            .as_ref()
+            .map(|dir| dir.join(parameters.bench))
+    }
+
+    /// Writes the report, unless exporting is disabled.
+    pub fn write(&self) -> io::Result<()> {
+        let Some(dir) = self.dir() else {
+            return Ok(());
+        };
+
+        fs::create_dir_all(&dir)?;
+
+        let file = BufWriter::new(fs::File::create(dir.join("results.json"))?);
+        serde_json::to_writer_pretty(file, self)?;
+
+        let mut scenarios = Vec::new();
+        for case in &self.cases {
+            if !scenarios.contains(&&case.scenario) {
+                scenarios.push(&case.scenario);
+            }
+        }
+
+        for scenario in scenarios {
+            self.write_summary_csv(&dir, scenario)?;
+        }
+
+        self.write_measurements_csv(&dir)?;
+        self.write_latency_csv(&dir)?;
+        write_charts(&dir, &self.charts())?;
+
+        println!("Results written to {}", dir.display());
+        Ok(())
+    }
+
+    /// Writes the report, then saves and compares baselines as configured. Exits the process
+    /// with an error if a case regressed compared to the baseline.
+    pub fn finish(&self) {
+        if let Err(err) = self.write() {
+            eprintln!("error: failed to write results: {err}");
+        }
+
+        let config = &self.metadata.parameters;
+        if config.update_readme {
+            let path = readme::path(config.bench);
+            let charts = self.charts();
+            match readme::update(&path, &readme::results(self, &charts), &charts) {
+                Ok(()) => println!("Results section of {} updated", path.display()),
+                Err(err) => eprintln!("error: failed to update {}: {err}", path.display()),
+            }
+        }
+
+        let baseline = config.baseline.as_ref().map(|name| {
+            let path = config.baseline_path(name);
+            Baseline::load(&path).unwrap_or_else(|err| {
+                eprintln!("error: failed to load baseline {}: {err}", path.display());
+                std::process::exit(2);
+            })
+        });
+
+        if let Some(name) = &config.save_baseline {
+            let path = config.baseline_path(name);
+            match Baseline::from_report(name, self).save(&path) {
+                Ok(()) => println!("Baseline `{name}` saved to {}", path.display()),
+                Err(err) => eprintln!("error: failed to save baseline {}: {err}", path.display()),
+            }
+        }
+
+        let Some(baseline) = baseline else {
+            return;
+        };
+
+        let current = Baseline::from_report("current", self);
+        println!(
+            "Compared to baseline `{}` (commit: {}, tokio: {}):",
+            baseline.name,
+            baseline.git_commit.as_deref().unwrap_or("unknown"),
+            baseline.tokio_version.as_deref().unwrap_or("unknown"),
+        );
+        if !baseline.is_comparable(&current) {
+            println!("Warning: the baseline was run with different parameters");
+        }
+
+        let rows = baseline.compare(&current, config.regression_threshold);
+        let mut table = Table::new(&rows);
+        for (i, row) in rows.iter().enumerate() {
+            match row.verdict {
+                Verdict::Regressed => table.modify(Rows::one(i + 1), Color::FG_RED),
+                Verdict::Improved => table.modify(Rows::one(i + 1), Color::FG_GREEN),
+                Verdict::Unchanged => &mut table,
+            };
+        }
+        config.print_table(&mut table);
+
+        let regressions = rows
+            .iter()
+            .filter(|row| row.verdict == Verdict::Regressed)
+            .count();
+        if regressions > 0 {
+            eprintln!(
+                "error: {regressions} case(s) regressed by more than {} compared to baseline `{}`",
+                format_percentage(&config.regression_threshold),
+                baseline.name
+            );
+            std::process::exit(1);
+        }
+    }
+
+    /// Returns the charts of the latency, throughput and load cases, skipping the scenarios
+    /// that weren't run.
+    pub fn charts(&self) -> Vec<Chart> {
+        // The runtime is only needed to tell the cases apart if there are multiple.
+        let runtimes = self.metadata.parameters.runtimes.len();
+        let label = |case: &Case| match runtimes {
+            1 => case.actor.clone(),
+            _ => format!("{} ({})", case.actor, case.runtime),
+        };
+        let cases = |scenario: &'static str| {
+            self.cases
+                .iter()
+                .filter(move |case| case.scenario == scenario)
+        };
+
+        let latency = cases("latency")
+            .filter_map(|case| Some((label(case), &case.latency.as_ref()?.histogram)))
+            .collect::<Vec<_>>();
+        let throughput = cases("throughput")
+            .filter(|case| !case.measurements.is_empty())
+            .map(|case| {
+                let throughputs = case.measurements.iter().map(|m| m.throughput).collect();
+                (label(case), throughputs)
+            })
+            .collect::<Vec<_>>();
+        let load = cases("load")
+            .filter_map(|case| Some((label(case), case.summary.get("steady_load")?.as_f64()?)))
+            .collect::<Vec<_>>();
+
+        let mut charts = Vec::new();
+        if !latency.is_empty() {
+            let title = "Latency by percentile";
+            charts.push(Chart {
+                file_name: "latency_percentiles.svg",
+                title,
+                svg: chart::latency_percentiles(title, &latency),
+            });
+        }
+        if !throughput.is_empty() {
+            let title = "Throughput per iteration";
+            charts.push(Chart {
+                file_name: "throughput.svg",
+                title,
+                svg: chart::box_plot(title, "Tasks per second", &throughput, format_throughput),
+            });
+        }
+        if !load.is_empty() {
+            let title = "Steady-state load of the actor task";
+            charts.push(Chart {
+                file_name: "load.svg",
+                title,
+                svg: chart::bar_chart(title, "Load", &load, format_percentage),
+            });
+        }
+        charts
+    }
+
+    fn write_summary_csv(&self, dir: &Path, scenario: &str) -> io::Result<()> {
+        let path = dir.join(format!("{}.csv", scenario.replace('/', "_")));
+        let mut file = BufWriter::new(fs::File::create(path)?);
+
+        let cases = self.cases.iter().filter(|case| case.scenario == scenario);
+        for (i, case) in cases.enumerate() {
+            let Value::Object(summary) = &case.summary else {
+                continue;
+            };
+
+            if i == 0 {
+                let header = ["id", "runtime"]
+                    .into_iter()
+                    .chain(summary.keys().map(String::as_str))
+                    .map(csv_field)
+                    .collect::<Vec<_>>();
+                writeln!(file, "{}", header.join(","))?;
+            }
+
+            let fields = [case.id.as_str(), case.runtime.as_str()]
+                .into_iter()
+                .map(csv_field)
+                .chain(summary.values().map(csv_value))
+                .collect::<Vec<_>>();
+            writeln!(file, "{}", fields.join(","))?;
+        }
+
+        file.flush()
+    }
+
+    fn write_measurements_csv(&self, dir: &Path) -> io::Result<()> {
+        let path = dir.join("throughput_measurements.csv");
+        let mut file = BufWriter::new(fs::File::create(path)?);
+        writeln!(file, "id,runtime,iteration,elapsed_ns,throughput")?;
+
+        for case in &self.cases {
+            for (i, measurement) in case.measurements.iter().enumerate() {
+                writeln!(
+                    file,
+                    "{},{},{},{},{}",
+                    csv_field(&case.id),
+                    csv_field(&case.runtime),
+                    i,
+                    measurement.elapsed.as_nanos(),
+                    measurement.throughput
+                )?;
+            }
+        }
+
+        file.flush()
+    }
+
+    fn write_latency_csv(&self, dir: &Path) -> io::Result<()> {
+        let path = dir.join("latency_histogram.csv");
+        let mut file = BufWriter::new(fs::File::create(path)?);
+        writeln!(file, "id,runtime,latency_ns,count")?;
+
+        for case in &self.cases {
+            let Some(latency) = &case.latency else {
+                continue;
+            };
+
+            let (id, runtime) = (csv_field(&case.id), csv_field(&case.runtime));
+            for (value, count) in latency.histogram.buckets() {
+                writeln!(file, "{},{},{},{}", id, runtime, value.as_nanos(), count)?;
+            }
+        }
+
+        file.flush()
+    }
+}
+
+/// Writes every chart to its file in `dir`.
+pub(crate) fn write_charts(dir: &Path, charts: &[Chart]) -> io::Result<()> {
+    for chart in charts {
+        fs::write(dir.join(chart.file_name), &chart.svg)?;
+    }
+    Ok(())
+}
+
+fn to_value(row: &impl Serialize) -> Value {
+    serde_json::to_value(row).expect("rows are always serializable")
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn csv_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => csv_field(s),
+        other => csv_field(&other.to_string()),
+    }
+}
@@ -0,0 +1,302 @@
+use std::time::Duration;
+
+use super::stats::Summary;
+
+/// The number of bits of precision of a [`Histogram`]. Every power of two range is split into
+/// `2^SUB_BUCKET_BITS` buckets.
+const SUB_BUCKET_BITS: u32 = 7;
+const SUB_BUCKETS: u64 = 1 << SUB_BUCKET_BITS;
+
+/// A log-bucketed latency histogram, in the style of HdrHistogram.
+///
+/// Values below 128ns are recorded exactly. Larger values are recorded in buckets that are 1/128th
+/// of their power of two range wide, so every quantile has a relative error of at most 0.4%,
+/// while the histogram takes at most 58KiB regardless of the number of samples. Histograms can be
+/// merged, e.g. to combine the histograms of multiple iterations or threads.
+#[derive(Debug, Clone, Default)]
+pub struct Histogram {
+    /// The number of samples per bucket. Only grows as far as the largest recorded bucket.
+    counts: Vec<u64>,
+    /// Count, mean, standard deviation, minimum and maximum of all samples in nanoseconds, which
+    /// don't need the approximation of the buckets.
+    summary: Summary,
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, value: Duration) {
+        self.record_nanos(value.as_nanos().min(u64::MAX as u128) as u64);
+    }
+
+    pub fn record_nanos(&mut self, nanos: u64) {
+        let index = bucket_index(nanos);
+        if index >= self.counts.len() {
+            self.counts.resize(index + 1, 0);
+        }
+        self.counts[index] += 1;
+        self.summary.add(nanos as f64);
+    }
+
+    /// Removes all samples, keeping the allocated buckets.
+    pub fn clear(&mut self) {
+        self.counts.fill(0);
+        self.summary = Summary::new();
+    }
+
+    /// Adds all samples of `other` to this histogram.
+    pub fn merge(&mut self, other: &Histogram) {
+        if other.is_empty() {
+            return;
+        }
+
+        if other.counts.len() > self.counts.len() {
+            self.counts.resize(other.counts.len(), 0);
+        }
+        for (count, other) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other;
+        }
+        self.summary.merge(&other.summary);
+    }
+
+    /// Returns the number of samples.
+    pub fn len(&self) -> u64 {
+        self.summary.count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.summary.is_empty()
+    }
+
+    /// Returns the summary statistics of all samples in nanoseconds.
+    pub fn summary(&self) -> &Summary {
+        &self.summary
+    }
+
+    /// Returns the mean of all samples, or zero if the histogram is empty.
+    pub fn mean(&self) -> Duration {
+        self.summary.mean().map_or(Duration::ZERO, nanos)
+    }
+
+    /// Returns the sample standard deviation, or zero if there are less than two samples.
+    pub fn std_dev(&self) -> Duration {
+        self.summary.std_dev().map_or(Duration::ZERO, nanos)
+    }
+
+    /// Returns the exact smallest sample, or zero if the histogram is empty.
+    pub fn min(&self) -> Duration {
+        self.summary.min().map_or(Duration::ZERO, nanos)
+    }
+
+    /// Returns the exact largest sample, or zero if the histogram is empty.
+    pub fn max(&self) -> Duration {
+        self.summary.max().map_or(Duration::ZERO, nanos)
+    }
+
+    /// Returns the value below which a fraction `quantile` of the samples fall, or zero if the
+    /// histogram is empty.
+    pub fn quantile(&self, quantile: f64) -> Duration {
+        if self.is_empty() {
+            return Duration::ZERO;
+        }
+
+        let rank = ((quantile.clamp(0.0, 1.0) * self.len() as f64).ceil() as u64).max(1);
+        let (min, max) = (self.min().as_nanos() as u64, self.max().as_nanos() as u64);
+
+        let mut seen = 0;
+        for (index, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                let nanos = bucket_value(index).clamp(min, max);
+                return Duration::from_nanos(nanos);
+            }
+        }
+
+        self.max()
+    }
+
+    /// Returns the value and count of every non-empty bucket, in increasing order. The value is
+    /// the middle of the bucket.
+    pub fn buckets(&self) -> impl Iterator<Item = (Duration, u64)> + '_ {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(index, count)| (Duration::from_nanos(bucket_value(index)), *count))
+    }
+}
+
+/// Converts a statistic in nanoseconds to a [`Duration`].
+pub(crate) fn nanos(nanos: f64) -> Duration {
+    Duration::from_nanos(nanos.round() as u64)
+}
+
+fn bucket_index(nanos: u64) -> usize {
+    if nanos < SUB_BUCKETS {
+        return nanos as usize;
+    }
+
+    let exponent = u64::BITS - 1 - nanos.leading_zeros();
+    let shift = exponent - SUB_BUCKET_BITS;
+    let sub_bucket = (nanos >> shift) - SUB_BUCKETS;
+    (SUB_BUCKETS + shift as u64 * SUB_BUCKETS + sub_bucket) as usize
+}
+
+/// Returns the middle of the bucket at `index`.
+fn bucket_value(index: usize) -> u64 {
+    let index = index as u64;
+    if index < SUB_BUCKETS {
+        return index;
+    }
+
+    let shift = (index - SUB_BUCKETS) / SUB_BUCKETS;
+    let sub_bucket = (index - SUB_BUCKETS) % SUB_BUCKETS;
+    let lower = (SUB_BUCKETS + sub_bucket) << shift;
+    lower + (1 << shift) / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the exact quantile of `sorted`, with the same rank as [`Histogram::quantile`].
+    fn exact_quantile(sorted: &[u64], quantile: f64) -> u64 {
+        let rank = ((quantile * sorted.len() as f64).ceil() as usize).max(1);
+        sorted[rank - 1]
+    }
+
+    #[test]
+    fn empty_histogram_is_zero() {
+        let histogram = Histogram::new();
+        assert!(histogram.is_empty());
+        assert_eq!(histogram.len(), 0);
+        assert_eq!(histogram.quantile(0.5), Duration::ZERO);
+        assert_eq!(histogram.min(), Duration::ZERO);
+        assert_eq!(histogram.max(), Duration::ZERO);
+        assert_eq!(histogram.buckets().count(), 0);
+    }
+
+    #[test]
+    fn small_values_are_exact() {
+        let mut histogram = Histogram::new();
+        let values = (0..SUB_BUCKETS).collect::<Vec<_>>();
+        for &value in &values {
+            histogram.record_nanos(value);
+        }
+
+        for quantile in [0.0, 0.1, 0.5, 0.9, 0.99, 1.0] {
+            let expected = exact_quantile(&values, quantile);
+            assert_eq!(histogram.quantile(quantile).as_nanos() as u64, expected);
+        }
+    }
+
+    #[test]
+    fn bucket_boundaries() {
+        // Buckets of 1ns up to 255ns, then buckets of 2ns from 256ns, 4ns from 512ns, and so on.
+        assert_eq!(bucket_index(127), 127);
+        assert_eq!(bucket_index(128), 128);
+        assert_eq!(bucket_index(255), 255);
+        assert_eq!(bucket_index(256), 256);
+        assert_eq!(bucket_index(257), 256);
+        assert_eq!(bucket_index(258), 257);
+        assert_eq!(bucket_index(511), 383);
+        assert_eq!(bucket_index(512), 384);
+        assert_eq!(bucket_index(515), 384);
+        assert_eq!(bucket_index(516), 385);
+
+        assert_eq!(bucket_value(127), 127);
+        assert_eq!(bucket_value(128), 128);
+        assert_eq!(bucket_value(256), 257);
+        assert_eq!(bucket_value(383), 511);
+        assert_eq!(bucket_value(384), 514);
+
+        // Every bucket contains its own middle, and the buckets are in increasing order.
+        for index in 0..bucket_index(u64::MAX) {
+            assert_eq!(bucket_index(bucket_value(index)), index);
+            assert!(bucket_value(index) < bucket_value(index + 1));
+        }
+    }
+
+    #[test]
+    fn largest_values_do_not_overflow() {
+        let mut histogram = Histogram::new();
+        histogram.record(Duration::MAX);
+        histogram.record_nanos(u64::MAX);
+        assert_eq!(histogram.len(), 2);
+        assert_eq!(histogram.quantile(1.0), Duration::from_nanos(u64::MAX));
+    }
+
+    #[test]
+    fn quantiles_are_within_relative_error() {
+        // Spread over several powers of two, with a long tail.
+        let mut values = (0..100_000u64)
+            .map(|i| 1_000 + i * i / 1_000)
+            .collect::<Vec<_>>();
+        values.sort();
+
+        let mut histogram = Histogram::new();
+        for &value in &values {
+            histogram.record_nanos(value);
+        }
+
+        for quantile in [0.0, 0.01, 0.25, 0.5, 0.75, 0.9, 0.99, 0.999, 0.9999, 1.0] {
+            let expected = exact_quantile(&values, quantile) as f64;
+            let actual = histogram.quantile(quantile).as_nanos() as f64;
+            let error = (actual - expected).abs() / expected;
+            assert!(
+                error <= 0.004,
+                "quantile {quantile}: {actual} != {expected} ({:.3}%)",
+                error * 100.0
+            );
+        }
+
+        assert_eq!(histogram.min(), Duration::from_nanos(values[0]));
+        assert_eq!(
+            histogram.max(),
+            Duration::from_nanos(values[values.len() - 1])
+        );
+    }
+
+    #[test]
+    fn merge_equals_recording_everything() {
+        let (mut first, mut second, mut all) =
+            (Histogram::new(), Histogram::new(), Histogram::new());
+        for i in 0..10_000u64 {
+            let value = i * 37 % 5_000;
+            first.record_nanos(value);
+            all.record_nanos(value);
+        }
+        // Larger values, so the second histogram has more buckets than the first.
+        for i in 0..5_000u64 {
+            let value = 100_000 + i * 7;
+            second.record_nanos(value);
+            all.record_nanos(value);
+        }
+
+        let mut merged = first.clone();
+        merged.merge(&second);
+        merged.merge(&Histogram::new());
+
+        assert_eq!(merged.len(), all.len());
+        assert_eq!(merged.min(), all.min());
+        assert_eq!(merged.max(), all.max());
+        assert_eq!(merged.mean(), all.mean());
+        assert_eq!(
+            merged.buckets().collect::<Vec<_>>(),
+            all.buckets().collect::<Vec<_>>()
+        );
+        for quantile in [0.0, 0.5, 0.9, 0.99, 1.0] {
+            assert_eq!(merged.quantile(quantile), all.quantile(quantile));
+        }
+
+        // Merging into an empty histogram copies the other one.
+        let mut empty = Histogram::new();
+        empty.merge(&all);
+        assert_eq!(
+            empty.buckets().collect::<Vec<_>>(),
+            all.buckets().collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! The shared benchmark harness used by all actor benchmarks.
+use std::time::{Duration, Instant};
+
+use tokio::{runtime::Runtime, sync::mpsc};
+
+use crate::_01::Actor;
+
+mod alloc;
+mod baseline;
+mod chart;
+mod config;
+mod export;
+mod histogram;
+mod open_loop;
+mod readme;
+mod registry;
+mod resources;
+mod result;
+mod runner;
+mod runtime;
+mod runtime_metrics;
+mod stats;
+mod system;
+mod table;
+
+pub use alloc::{AllocationResult, AllocationStats, CountingAllocator};
+pub use baseline::{Baseline, BaselineCase, ComparisonRow, SIGNIFICANCE_LEVEL, Unit, Verdict};
+pub use chart::Chart;
+pub use config::{Config, parse_duration};
+pub use export::{Case, Metadata, Report, TaskInterval, as_nanos, as_optional_nanos};
+pub use histogram::Histogram;
+pub use open_loop::{Arrival, DEFAULT_RATES, OpenLoopPoint, OpenLoopResult};
+pub use readme::{RESULTS_END, RESULTS_START, ReportTable};
+pub use registry::{ActorFactory, RegisteredActor, Registry, Scenario, ScenarioResult};
+pub use resources::{ResourceSampler, ResourceUsage};
+pub use result::{LatencyResult, LoadResult, ThroughputMeasurement, ThroughputResult};
+pub use runner::Runner;
+pub use runtime::{Flavor, RuntimeColumns, RuntimeConfig, Runtimes};
+pub use runtime_metrics::RuntimeResult;
+#[cfg(tokio_unstable)]
+pub use runtime_metrics::RuntimeSampler;
+pub use stats::{
+    BOOTSTRAP_RESAMPLES, CONFIDENCE_LEVEL, ConfidenceInterval, OutlierMethod, Outliers, Summary,
+    bootstrap, mann_whitney_u, mean, median, normal_cdf, quantile, std_dev,
+};
+pub use system::SystemInfo;
+pub use table::{
+    AllocationRow, LatencyRow, LoadRow, OpenLoopRow, RuntimeRow, SummaryRow, TableStyle,
+    ThroughputRow, ToRow, VsBest, comparison_table, format_bytes, format_duration,
+    format_duration_ci, format_outliers, format_percentage, format_ratio, format_throughput,
+};
+
+pub struct Bencher<'a> {
+    /// The runtime that runs the actor.
+    rt: &'a Runtime,
+    /// The runtime that sends the tasks.
+    producer: &'a Runtime,
+    /// The sender that sends tasks to the actor.
+    task_sender: Option<mpsc::Sender<Instant>>,
+    /// The receiver that receives results from the actor.
+    result_receiver: mpsc::Receiver<Duration>,
+    /// Whether the load benchmark collects runtime-wide metrics.
+    runtime_metrics: bool,
+    /// Number of iterations to run before the measured ones.
+    warmup: usize,
+    /// How the measured iterations are classified as outliers.
+    outlier_method: OutlierMethod,
+    /// Time the consumer spends on every result in the throughput benchmark.
+    consumer_delay: Duration,
+}
+
+impl<'a> Bencher<'a> {
+    pub fn new(
+        rt: &'a Runtime,
+        task_sender: mpsc::Sender<Instant>,
+        result_receiver: mpsc::Receiver<Duration>,
+    ) -> Self {
+        Self {
+            rt,
+            producer: rt,
+            task_sender: Some(task_sender),
+            result_receiver,
+            runtime_metrics: false,
+            warmup: 0,
+            outlier_method: OutlierMethod::default(),
+            consumer_delay: Duration::ZERO,
+        }
+    }
+
+    /// Sends the tasks from `producer` instead of the runtime of the actor.
+    pub fn with_producer(mut self, producer: &'a Runtime) -> Self {
+        self.producer = producer;
+        self
+    }
+
+    /// Also collects the metrics of the whole actor runtime in [`Bencher::benchmark_load`].
+    /// Requires `--cfg tokio_unstable`, and is ignored without it.
+    pub fn with_runtime_metrics(mut self, runtime_metrics: bool) -> Self {
+        self.runtime_metrics = runtime_metrics;
+        self
+    }
+
+    /// Runs `warmup` iterations before the measured iterations of the throughput, latency and
+    /// load benchmarks, e.g. to grow the buffers of the actor and the channels.
+    pub fn with_warmup(mut self, warmup: usize) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    pub fn with_outlier_method(mut self, outlier_method: OutlierMethod) -> Self {
+        self.outlier_method = outlier_method;
+        self
+    }
+
+    /// Busy-waits for `delay` after receiving every result in [`Bencher::benchmark_throughput`],
+    /// to simulate a consumer that can't keep up with the actor.
+    pub fn with_consumer_delay(mut self, delay: Duration) -> Self {
+        self.consumer_delay = delay;
+        self
+    }
+
+    pub fn benchmark_throughput<A: Actor>(
+        &mut self,
+        actor: A,
+        num_tasks: usize,
+        iters: usize,
+    ) -> ThroughputResult {
+        let before = AllocationStats::start();
+        let handle = self.rt.spawn(actor.run());
+
+        // Take the sender
+        let task_sender = self.task_sender.take().unwrap();
+
+        let mut measurements = Vec::with_capacity(iters);
+
+        for iteration in 0..self.warmup + iters {
+            let sender = task_sender.clone();
+            let start = self.producer.spawn(async move {
+                // Start here, don't want to measure the `spawn` duration.
+                let start = Instant::now();
+
+                for _ in 0..num_tasks {
+                    sender.send(Instant::now()).await.unwrap();
+                }
+
+                start
+            });
+
+            for _ in 0..num_tasks {
+                let _ = self.rt.block_on(self.result_receiver.recv());
+                consume(self.consumer_delay);
+            }
+
+            let end = Instant::now();
+            let start = self.rt.block_on(start).unwrap();
+
+            if iteration < self.warmup {
+                continue;
+            }
+
+            let elapsed = end.duration_since(start);
+            let throughput = num_tasks as f64 / elapsed.as_secs_f64();
+
+            measurements.push(ThroughputMeasurement {
+                elapsed,
+                throughput,
+            });
+        }
+
+        drop(task_sender);
+        let metrics = self.rt.block_on(handle).unwrap();
+
+        ThroughputResult::new(
+            measurements,
+            metrics,
+            AllocationResult {
+                stats: AllocationStats::current().since(&before),
+                tasks: (num_tasks * (self.warmup + iters)) as u64,
+            },
+            self.outlier_method,
+        )
+    }
+
+    /// This benchmark measures the individual latency of each task, where latency is defined as the time between
+    /// - The task being sent to the actor
+    /// - The task being processed by the actor and queued on the result channel (NOT the last mile result communication)
+    pub fn benchmark_latency<A: Actor>(
+        &mut self,
+        actor: A,
+        num_tasks: usize,
+        iters: usize,
+    ) -> LatencyResult {
+        self.rt.spawn(actor.run());
+        let task_sender = self.task_sender.take().unwrap();
+
+        let mut iterations = Vec::with_capacity(iters);
+
+        for iteration in 0..self.warmup + iters {
+            let sender = task_sender.clone();
+            self.producer.spawn(async move {
+                for _ in 0..num_tasks {
+                    sender.send(Instant::now()).await.unwrap();
+                }
+            });
+
+            let mut histogram = Histogram::new();
+            for _ in 0..num_tasks {
+                let result = self.rt.block_on(self.result_receiver.recv()).unwrap();
+                histogram.record(result);
+            }
+            if iteration >= self.warmup {
+                iterations.push(histogram);
+            }
+        }
+
+        LatencyResult::new(iterations, self.outlier_method)
+    }
+
+    /// Sends `num_tasks` tasks at each of the offered `rates` (in tasks per second) from a
+    /// separate thread, following the intended schedule of `arrival` regardless of how fast
+    /// the actor processes them. Latency is measured from the intended send time of every task.
+    pub fn benchmark_open_loop<A: Actor>(
+        &mut self,
+        actor: A,
+        num_tasks: usize,
+        rates: &[f64],
+        arrival: Arrival,
+    ) -> OpenLoopResult {
+        self.rt.spawn(actor.run());
+        let task_sender = self.task_sender.take().unwrap();
+
+        let mut points = Vec::with_capacity(rates.len());
+
+        for &rate in rates {
+            let schedule = arrival.schedule(rate, num_tasks);
+            let mut latency = Histogram::new();
+
+            let (sender, schedule) = (&task_sender, &schedule);
+            let (elapsed, max_send_delay) = std::thread::scope(|scope| {
+                let start = Instant::now();
+                let generator = scope.spawn(move || open_loop::generate(sender, start, schedule));
+
+                for _ in 0..num_tasks {
+                    let result = self.rt.block_on(self.result_receiver.recv()).unwrap();
+                    latency.record(result);
+                }
+
+                (start.elapsed(), generator.join().unwrap())
+            });
+
+            // The schedule spans `num_tasks / rate` seconds, but the last burst or task is sent
+            // before its end, so the results can all arrive earlier than that.
+            let span = Duration::from_secs_f64(num_tasks as f64 / rate);
+            points.push(OpenLoopPoint {
+                offered_rate: rate,
+                achieved_throughput: num_tasks as f64 / elapsed.max(span).as_secs_f64(),
+                latency,
+                max_send_delay,
+            });
+        }
+
+        OpenLoopResult { points }
+    }
+
+    /// Measures the load of the actor task over `iters` iterations. The task metrics are sampled
+    /// after every iteration including the warm-up, which is excluded from the steady-state
+    /// statistics. The resource usage is only sampled for the measured iterations.
+    pub fn benchmark_load<A: Actor>(
+        &mut self,
+        actor: A,
+        num_tasks: usize,
+        iters: usize,
+    ) -> LoadResult {
+        #[cfg(tokio_unstable)]
+        let runtime_sampler = self
+            .runtime_metrics
+            .then(|| RuntimeSampler::start(self.rt.handle()));
+
+        let monitor = tokio_metrics::TaskMonitor::new();
+        let task_monitor = monitor.clone();
+        let mut task_intervals = monitor.intervals();
+        self.rt.spawn(task_monitor.instrument(actor.run()));
+
+        let task_sender = self.task_sender.take().unwrap();
+
+        let mut resources = Vec::with_capacity(iters);
+        let mut intervals = Vec::with_capacity(self.warmup + iters);
+
+        for iteration in 0..self.warmup + iters {
+            let sampler = (iteration >= self.warmup)
+                .then(ResourceSampler::start)
+                .flatten();
+
+            let sender = task_sender.clone();
+            self.producer.spawn(async move {
+                for _ in 0..num_tasks {
+                    sender.send(Instant::now()).await.unwrap();
+                }
+            });
+
+            for _ in 0..num_tasks {
+                let _ = self.rt.block_on(self.result_receiver.recv()).unwrap();
+            }
+
+            resources.extend(sampler.and_then(ResourceSampler::finish));
+            intervals.extend(task_intervals.next());
+        }
+
+        let poll_durations = intervals[self.warmup..]
+            .iter()
+            .map(|interval| interval.mean_poll_duration().as_nanos() as f64)
+            .collect::<Vec<_>>();
+
+        LoadResult {
+            metrics: monitor.cumulative(),
+            intervals,
+            warmup: self.warmup,
+            outliers: Outliers::classify(&poll_durations, self.outlier_method),
+            resources,
+            #[cfg(tokio_unstable)]
+            runtime: runtime_sampler.map(|sampler| Box::new(sampler.finish())),
+            #[cfg(not(tokio_unstable))]
+            runtime: None,
+        }
+    }
+}
+
+/// Spins for `delay`. Sleeping is too coarse for the delays of a single result.
+fn consume(delay: Duration) {
+    if delay.is_zero() {
+        return;
+    }
+
+    let start = Instant::now();
+    while start.elapsed() < delay {
+        std::hint::spin_loop();
+    }
+}
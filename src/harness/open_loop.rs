@@ -0,0 +1,146 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use tokio::sync::mpsc;
+
+use super::Histogram;
+
+/// The default offered loads of the open-loop sweep, in tasks per second.
+pub const DEFAULT_RATES: [f64; 5] = [10_000.0, 25_000.0, 50_000.0, 100_000.0, 200_000.0];
+
+/// The generator sleeps until the next send time if it is further away than this, and yields
+/// otherwise, because sleeping overshoots by tens of microseconds.
+const SLEEP_THRESHOLD: Duration = Duration::from_micros(200);
+
+/// How the tasks of an open-loop run arrive.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Arrival {
+    /// Tasks arrive at a fixed interval.
+    Fixed,
+    /// Tasks arrive independently of each other, with exponentially distributed gaps.
+    Poisson,
+    /// Tasks arrive in bursts of `size` tasks at once, at a fixed interval.
+    Bursty { size: usize },
+}
+
+impl Arrival {
+    /// The default burst size of [`Arrival::Bursty`].
+    pub const DEFAULT_BURST_SIZE: usize = 100;
+
+    /// Parses `fixed`, `poisson`, `bursty` or `bursty:<SIZE>`.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.split_once(':') {
+            None if value == "fixed" => Ok(Arrival::Fixed),
+            None if value == "poisson" => Ok(Arrival::Poisson),
+            None if value == "bursty" => Ok(Arrival::Bursty {
+                size: Self::DEFAULT_BURST_SIZE,
+            }),
+            Some(("bursty", size)) => match size.parse() {
+                Ok(size) if size > 0 => Ok(Arrival::Bursty { size }),
+                _ => Err(format!("invalid burst size `{size}`")),
+            },
+            _ => Err(format!("unknown arrival pattern `{value}`")),
+        }
+    }
+
+    /// Returns the intended send times of `num_tasks` tasks offered at `rate` tasks per second,
+    /// relative to the start of the run.
+    pub fn schedule(&self, rate: f64, num_tasks: usize) -> Vec<Duration> {
+        let interval = 1.0 / rate;
+
+        match *self {
+            Arrival::Fixed => (0..num_tasks)
+                .map(|i| Duration::from_secs_f64(i as f64 * interval))
+                .collect(),
+            Arrival::Poisson => {
+                let mut rng = fastrand::Rng::new();
+                let mut time = 0.0;
+                (0..num_tasks)
+                    .map(|_| {
+                        let offset = Duration::from_secs_f64(time);
+                        // `1.0 - f64()` is in (0, 1], so the logarithm is finite.
+                        time += -(1.0 - rng.f64()).ln() * interval;
+                        offset
+                    })
+                    .collect()
+            }
+            Arrival::Bursty { size } => (0..num_tasks)
+                .map(|i| Duration::from_secs_f64((i / size * size) as f64 * interval))
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for Arrival {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Arrival::Fixed => f.write_str("fixed"),
+            Arrival::Poisson => f.write_str("poisson"),
+            Arrival::Bursty { size } => write!(f, "bursty:{size}"),
+        }
+    }
+}
+
+/// Sends a task for every send time of `schedule` from the current thread, blocking until it is
+/// due. Returns the largest delay of a send after its intended send time.
+///
+/// Every task carries its intended send time instead of the actual one, so that latency is
+/// measured from when the task should have been sent. Otherwise, a full channel or a slow
+/// generator would delay the sends, and hide the queueing delay behind them (coordinated
+/// omission).
+pub(crate) fn generate(
+    sender: &mpsc::Sender<Instant>,
+    start: Instant,
+    schedule: &[Duration],
+) -> Duration {
+    let mut max_send_delay = Duration::ZERO;
+
+    for offset in schedule {
+        let intended = start + *offset;
+
+        loop {
+            let now = Instant::now();
+            if now >= intended {
+                break;
+            }
+
+            let remaining = intended - now;
+            if remaining > SLEEP_THRESHOLD {
+                std::thread::sleep(remaining - SLEEP_THRESHOLD / 2);
+            } else {
+                std::thread::yield_now();
+            }
+        }
+
+        sender.blocking_send(intended).unwrap();
+        max_send_delay = max_send_delay.max(intended.elapsed());
+    }
+
+    max_send_delay
+}
+
+/// The result of the open-loop scenario: one point of the latency-throughput curve per offered
+/// load.
+#[derive(Debug, Clone)]
+pub struct OpenLoopResult {
+    pub points: Vec<OpenLoopPoint>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OpenLoopPoint {
+    /// The rate at which tasks were sent, in tasks per second.
+    pub offered_rate: f64,
+    /// The rate at which tasks were completed, in tasks per second, over the time until the last
+    /// result arrived, but at least the span of the schedule. It is at most the offered rate, and
+    /// falls below it once the actor can't keep up.
+    pub achieved_throughput: f64,
+    /// Latencies measured from the intended send time.
+    pub latency: Histogram,
+    /// The largest delay of a send after its intended send time. If this is large, the
+    /// generator or the task channel couldn't keep up with the offered load.
+    pub max_send_delay: Duration,
+}
@@ -0,0 +1,120 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use tabled::Table;
+
+use super::{Chart, Report, TableStyle, export::write_charts};
+
+/// The comment that starts the generated results in a README.
+pub const RESULTS_START: &str = "<!-- results:start -->";
+/// The comment that ends the generated results in a README.
+pub const RESULTS_END: &str = "<!-- results:end -->";
+
+const GIB: f64 = (1u64 << 30) as f64;
+
+/// A table of a [`Report`] that is published in the README.
+#[derive(Debug, Clone)]
+pub struct ReportTable {
+    pub title: String,
+    /// The table in markdown.
+    pub markdown: String,
+}
+
+impl ReportTable {
+    pub fn new(title: impl Into<String>, table: &Table) -> Self {
+        Self {
+            title: title.into(),
+            markdown: TableStyle::Markdown.render(&mut table.clone(), false),
+        }
+    }
+}
+
+/// Returns the path of the README of the benchmark `bench`.
+pub fn path(bench: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join(bench)
+        .join("README.md")
+}
+
+/// The directory next to the README that the charts are written to.
+const CHARTS_DIR: &str = "charts";
+
+/// Returns the generated results of `report`: the system, parameters and command of the run,
+/// followed by its tables and `charts`.
+pub fn results(report: &Report, charts: &[Chart]) -> String {
+    let (system, config) = (&report.metadata.system, &report.metadata.parameters);
+
+    let mut machine = vec![
+        system
+            .cpu_model
+            .as_deref()
+            .unwrap_or("Unknown CPU")
+            .to_string(),
+    ];
+    machine.push(match system.cpu_count {
+        1 => "1 Core".to_string(),
+        n => format!("{n} Cores"),
+    });
+    if let Some(memory) = system.memory_bytes {
+        machine.push(format!("{:.0}GiB RAM", memory as f64 / GIB));
+    }
+    machine.push(format!("{} {}", system.os, system.arch));
+
+    let unknown = |value: &Option<String>| value.clone().unwrap_or("unknown".to_string());
+
+    let mut results = vec![
+        format!("- Parameters: {}", config.to_string().replace('\n', ", ")),
+        format!("- System: {}", machine.join(", ")),
+        format!(
+            "- Toolchain: {}, tokio {}",
+            unknown(&system.rustc_version),
+            unknown(&system.tokio_version)
+        ),
+        format!("- Commit: `{}`", unknown(&system.git_commit)),
+        format!("- Command: `{}`", config.command()),
+    ];
+
+    for table in &report.tables {
+        results.push(format!("\n### {}\n{}", table.title, table.markdown));
+    }
+
+    if !charts.is_empty() {
+        results.push("\n### Charts".to_string());
+        for chart in charts {
+            results.push(format!(
+                "![{}]({CHARTS_DIR}/{})",
+                chart.title, chart.file_name
+            ));
+        }
+    }
+
+    results.join("\n")
+}
+
+/// Replaces the generated results in the README at `path` with `results`, and writes `charts`
+/// next to it. Fails if the README doesn't have both markers, so that hand-written parts are
+/// never overwritten.
+pub fn update(path: &Path, results: &str, charts: &[Chart]) -> io::Result<()> {
+    let readme = fs::read_to_string(path)?;
+
+    let missing = || {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("missing `{RESULTS_START}` or `{RESULTS_END}`"),
+        )
+    };
+    let start = readme.find(RESULTS_START).ok_or_else(missing)? + RESULTS_START.len();
+    let end = readme[start..].find(RESULTS_END).ok_or_else(missing)? + start;
+
+    if !charts.is_empty() {
+        let dir = path.with_file_name(CHARTS_DIR);
+        fs::create_dir_all(&dir)?;
+        write_charts(&dir, charts)?;
+    }
+
+    let updated = format!("{}\n{results}\n{}", &readme[..start], &readme[end..]);
+    fs::write(path, updated)
+}
@@ -0,0 +1,197 @@
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tokio::sync::mpsc;
+
+use crate::_01::Actor;
+
+use super::{
+    Bencher, Config, LatencyResult, LoadResult, OpenLoopResult, Runtimes, ThroughputResult,
+};
+
+/// Creates an [`Actor`] from the incoming task channel and the results channel.
+///
+/// This is implemented for all functions and closures with the right signature, like
+/// `FutureActor::<Constrained>::new` or `RandomSelectActor::new`.
+pub trait ActorFactory: 'static {
+    type Actor: Actor;
+
+    fn create(
+        &self,
+        incoming_tasks: mpsc::Receiver<Instant>,
+        results: mpsc::Sender<Duration>,
+    ) -> Self::Actor;
+}
+
+impl<F, A> ActorFactory for F
+where
+    F: Fn(mpsc::Receiver<Instant>, mpsc::Sender<Duration>) -> A + 'static,
+    A: Actor,
+{
+    type Actor = A;
+
+    fn create(
+        &self,
+        incoming_tasks: mpsc::Receiver<Instant>,
+        results: mpsc::Sender<Duration>,
+    ) -> A {
+        self(incoming_tasks, results)
+    }
+}
+
+/// A benchmark scenario that can be run for every registered actor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Scenario {
+    Throughput,
+    Latency,
+    Load,
+    /// Sweeps the offered load with an open-loop generator, see [`Bencher::benchmark_open_loop`].
+    OpenLoop,
+    /// The throughput scenario with a results channel that is smaller than the task channel and
+    /// a consumer that spends time on every result, so the actors have to handle backpressure.
+    SlowConsumer,
+}
+
+impl Scenario {
+    pub const ALL: [Scenario; 5] = [
+        Scenario::Throughput,
+        Scenario::Latency,
+        Scenario::Load,
+        Scenario::OpenLoop,
+        Scenario::SlowConsumer,
+    ];
+
+    /// The scenarios that are run if none are selected. The open-loop sweep takes a while and
+    /// the slow consumer mostly measures the consumer, so they only run when selected.
+    pub const DEFAULT: [Scenario; 3] = [Scenario::Throughput, Scenario::Latency, Scenario::Load];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scenario::Throughput => "throughput",
+            Scenario::Latency => "latency",
+            Scenario::Load => "load",
+            Scenario::OpenLoop => "open-loop",
+            Scenario::SlowConsumer => "slow-consumer",
+        }
+    }
+}
+
+/// The result of running a single [`Scenario`].
+#[derive(Debug)]
+pub enum ScenarioResult {
+    Throughput(ThroughputResult),
+    Latency(LatencyResult),
+    Load(LoadResult),
+    OpenLoop(OpenLoopResult),
+    SlowConsumer(ThroughputResult),
+}
+
+impl ScenarioResult {
+    /// Returns the per-iteration samples that actors are compared by, where lower is better:
+    /// the iteration durations (throughput, slow consumer), the median latencies (latency), or
+    /// the loads of the steady state (load). The open-loop scenario has no iterations.
+    pub fn samples(&self) -> Vec<f64> {
+        match self {
+            ScenarioResult::Throughput(result) | ScenarioResult::SlowConsumer(result) => result
+                .measurements
+                .iter()
+                .map(|measurement| measurement.elapsed.as_nanos() as f64)
+                .collect(),
+            ScenarioResult::Latency(result) => result
+                .iterations
+                .iter()
+                .map(|iteration| iteration.quantile(0.5).as_nanos() as f64)
+                .collect(),
+            ScenarioResult::Load(result) => result.steady_state_loads(),
+            ScenarioResult::OpenLoop(_) => Vec::new(),
+        }
+    }
+}
+
+type RunFn = dyn Fn(&Runtimes, Scenario, &Config) -> ScenarioResult;
+
+/// An actor in the [`Registry`].
+pub struct RegisteredActor {
+    name: &'static str,
+    run: Box<RunFn>,
+}
+
+impl RegisteredActor {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Creates a fresh actor with new channels and runs `scenario` on it.
+    pub fn run(&self, runtimes: &Runtimes, scenario: Scenario, config: &Config) -> ScenarioResult {
+        (self.run)(runtimes, scenario, config)
+    }
+}
+
+/// A list of named [`ActorFactory`]s to benchmark.
+#[derive(Default)]
+pub struct Registry {
+    actors: Vec<RegisteredActor>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers an actor under `name`.
+    pub fn register<F: ActorFactory>(&mut self, name: &'static str, factory: F) -> &mut Self {
+        // The scenario is dispatched inside the closure, so that the actor type is known
+        // statically and the actor future doesn't have to be boxed.
+        let run = move |runtimes: &Runtimes, scenario: Scenario, config: &Config| {
+            let (num_tasks, iters) = (config.num_tasks, config.iterations);
+            // Only the slow consumer has a results channel without room for a whole iteration.
+            let (result_capacity, consumer_delay) = match scenario {
+                Scenario::SlowConsumer => (config.result_capacity, config.consumer_delay),
+                _ => (num_tasks, Duration::ZERO),
+            };
+            let (task_sender, task_receiver) = mpsc::channel(config.channel_capacity());
+            let (result_sender, result_receiver) = mpsc::channel(result_capacity);
+
+            let actor = factory.create(task_receiver, result_sender);
+            let mut bencher = Bencher::new(&runtimes.actor, task_sender, result_receiver)
+                .with_producer(runtimes.producer())
+                .with_runtime_metrics(config.runtime_metrics)
+                .with_warmup(config.warmup_iterations)
+                .with_outlier_method(config.outlier_method)
+                .with_consumer_delay(consumer_delay);
+
+            match scenario {
+                Scenario::Throughput => ScenarioResult::Throughput(
+                    bencher.benchmark_throughput(actor, num_tasks, iters),
+                ),
+                Scenario::Latency => {
+                    ScenarioResult::Latency(bencher.benchmark_latency(actor, num_tasks, iters))
+                }
+                Scenario::Load => {
+                    ScenarioResult::Load(bencher.benchmark_load(actor, num_tasks, iters))
+                }
+                Scenario::OpenLoop => ScenarioResult::OpenLoop(bencher.benchmark_open_loop(
+                    actor,
+                    num_tasks,
+                    &config.rates,
+                    config.arrival,
+                )),
+                Scenario::SlowConsumer => ScenarioResult::SlowConsumer(
+                    bencher.benchmark_throughput(actor, num_tasks, iters),
+                ),
+            }
+        };
+
+        self.actors.push(RegisteredActor {
+            name,
+            run: Box::new(run),
+        });
+
+        self
+    }
+
+    pub fn actors(&self) -> &[RegisteredActor] {
+        &self.actors
+    }
+}
@@ -0,0 +1,214 @@
+use std::{
+    fs,
+    sync::mpsc,
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+use super::export::as_nanos;
+
+/// How often the sampler reads the resident set size.
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(5);
+
+/// The name of the sampler thread. Linux truncates thread names to 15 bytes, so it is shorter
+/// than that, to match the name in `/proc/self/task/<tid>/status`.
+const SAMPLER_THREAD_NAME: &str = "rss-sampler";
+
+/// A snapshot of the resource usage of this process, read from `/proc/self` on Linux.
+#[derive(Debug, Clone, Copy)]
+struct ProcessStats {
+    rss_bytes: u64,
+    voluntary_context_switches: u64,
+    involuntary_context_switches: u64,
+    user_time: Duration,
+    system_time: Duration,
+}
+
+impl ProcessStats {
+    fn read() -> Option<Self> {
+        let threads = thread_stats()?;
+        Some(Self {
+            rss_bytes: rss_bytes()?,
+            voluntary_context_switches: threads.iter().map(|t| t.voluntary_context_switches).sum(),
+            involuntary_context_switches: threads
+                .iter()
+                .map(|t| t.involuntary_context_switches)
+                .sum(),
+            user_time: ticks_to_duration(threads.iter().map(|t| t.user_ticks).sum()),
+            system_time: ticks_to_duration(threads.iter().map(|t| t.system_ticks).sum()),
+        })
+    }
+}
+
+/// The context switches and CPU time of a single thread, read from `/proc/self/task/<tid>`.
+#[derive(Debug, Clone, Copy)]
+struct ThreadStats {
+    voluntary_context_switches: u64,
+    involuntary_context_switches: u64,
+    user_ticks: u64,
+    system_ticks: u64,
+}
+
+/// Returns the stats of all threads except the sampler, so that the context switches and the
+/// CPU time cover the same threads. `/proc/self/status` only has the context switches of the
+/// main thread, so this reads every thread. Threads that have exited are not counted anymore.
+fn thread_stats() -> Option<Vec<ThreadStats>> {
+    let mut threads = Vec::new();
+
+    for task in fs::read_dir("/proc/self/task").ok()? {
+        let path = task.ok()?.path();
+        let (Ok(status), Ok(stat)) = (
+            fs::read_to_string(path.join("status")),
+            fs::read_to_string(path.join("stat")),
+        ) else {
+            // The thread exited in the meantime.
+            continue;
+        };
+        if status_field(&status, "Name") == Some(SAMPLER_THREAD_NAME) {
+            continue;
+        }
+
+        // The command name in the second field may contain spaces, so the fields are counted
+        // from the closing parenthesis after it, which is followed by the third field.
+        let mut fields = stat.get(stat.rfind(')')? + 1..)?.split_whitespace();
+        let user_ticks = fields.nth(11)?.parse().ok()?;
+        let system_ticks = fields.next()?.parse().ok()?;
+
+        threads.push(ThreadStats {
+            voluntary_context_switches: status_field(&status, "voluntary_ctxt_switches")?
+                .parse()
+                .ok()?,
+            involuntary_context_switches: status_field(&status, "nonvoluntary_ctxt_switches")?
+                .parse()
+                .ok()?,
+            user_ticks,
+            system_ticks,
+        });
+    }
+
+    Some(threads)
+}
+
+/// Returns the value of the `name` field of a `/proc/.../status` file.
+fn status_field<'a>(status: &'a str, name: &str) -> Option<&'a str> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+        .map(str::trim)
+}
+
+fn rss_bytes() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    // In kB, even though it is KiB.
+    let rss = status_field(&status, "VmRSS")?.trim_end_matches(" kB");
+    Some(rss.parse::<u64>().ok()? * 1024)
+}
+
+/// Returns the number of clock ticks per second of the CPU times in `/proc`, which is 100 on
+/// every Linux architecture in practice.
+fn clock_ticks_per_second() -> u64 {
+    #[cfg(unix)]
+    {
+        // SAFETY: `sysconf` has no preconditions.
+        let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+        if ticks > 0 {
+            return ticks as u64;
+        }
+    }
+
+    100
+}
+
+fn ticks_to_duration(ticks: u64) -> Duration {
+    Duration::from_nanos(ticks * (1_000_000_000 / clock_ticks_per_second()))
+}
+
+/// The resource usage of the process during one iteration. This includes the producer of the
+/// tasks and the harness itself, not just the actor, but not the sampler thread. The context
+/// switches and CPU time only count the threads that are still running at the end of the
+/// iteration.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ResourceUsage {
+    /// Wall-clock duration of the iteration.
+    #[serde(serialize_with = "as_nanos")]
+    pub elapsed: Duration,
+    /// Highest resident set size seen during the iteration.
+    pub peak_rss_bytes: u64,
+    /// Number of times a thread gave up the CPU, e.g. to wait for work.
+    pub voluntary_context_switches: u64,
+    /// Number of times a thread was preempted.
+    pub involuntary_context_switches: u64,
+    /// CPU time spent in user mode, with a resolution of one clock tick, usually 10ms.
+    #[serde(serialize_with = "as_nanos")]
+    pub user_time: Duration,
+    /// CPU time spent in kernel mode, with a resolution of one clock tick, usually 10ms.
+    #[serde(serialize_with = "as_nanos")]
+    pub system_time: Duration,
+}
+
+/// Samples the resource usage of the process on a background thread, from [`start`] until
+/// [`finish`]. Only available on Linux.
+///
+/// [`start`]: ResourceSampler::start
+/// [`finish`]: ResourceSampler::finish
+pub struct ResourceSampler {
+    start: ProcessStats,
+    started: Instant,
+    stop: mpsc::Sender<()>,
+    thread: JoinHandle<u64>,
+}
+
+impl ResourceSampler {
+    /// Starts sampling, or returns `None` if `/proc/self` can't be read.
+    pub fn start() -> Option<Self> {
+        let start = ProcessStats::read()?;
+        let (stop, stopped) = mpsc::channel();
+
+        let thread = std::thread::Builder::new()
+            .name(SAMPLER_THREAD_NAME.to_string())
+            .spawn(move || {
+                let mut peak_rss_bytes = 0;
+                // Stops when `stop` is dropped.
+                while let Err(mpsc::RecvTimeoutError::Timeout) =
+                    stopped.recv_timeout(SAMPLE_INTERVAL)
+                {
+                    if let Some(rss_bytes) = rss_bytes() {
+                        peak_rss_bytes = peak_rss_bytes.max(rss_bytes);
+                    }
+                }
+                peak_rss_bytes
+            })
+            .ok()?;
+
+        Some(Self {
+            start,
+            started: Instant::now(),
+            stop,
+            thread,
+        })
+    }
+
+    /// Stops sampling and returns the resource usage since [`ResourceSampler::start`].
+    pub fn finish(self) -> Option<ResourceUsage> {
+        let elapsed = self.started.elapsed();
+        // The sampler is stopped first, so that the end snapshot doesn't include it.
+        drop(self.stop);
+        let peak_rss_bytes = self.thread.join().ok()?;
+        let end = ProcessStats::read()?;
+
+        Some(ResourceUsage {
+            elapsed,
+            peak_rss_bytes: peak_rss_bytes.max(self.start.rss_bytes).max(end.rss_bytes),
+            voluntary_context_switches: end
+                .voluntary_context_switches
+                .saturating_sub(self.start.voluntary_context_switches),
+            involuntary_context_switches: end
+                .involuntary_context_switches
+                .saturating_sub(self.start.involuntary_context_switches),
+            user_time: end.user_time.saturating_sub(self.start.user_time),
+            system_time: end.system_time.saturating_sub(self.start.system_time),
+        })
+    }
+}
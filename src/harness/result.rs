@@ -0,0 +1,362 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio_metrics::TaskMetrics;
+
+use crate::_01::ActorMetrics;
+
+use super::{
+    AllocationResult, Histogram, OutlierMethod, Outliers, ResourceUsage, RuntimeResult,
+    export::as_nanos,
+    histogram::nanos,
+    stats::{ConfidenceInterval, Summary, bootstrap, mean, median},
+    table::calculate_load,
+};
+
+#[derive(Debug)]
+pub struct ThroughputResult {
+    /// Measurements.
+    pub measurements: Vec<ThroughputMeasurement>,
+    /// Metrics.
+    pub metrics: ActorMetrics,
+    /// Allocations made by the whole run, including spawning the actor, sending the tasks and
+    /// the warm-up.
+    pub allocations: AllocationResult,
+    /// Outliers among the iteration durations.
+    pub outliers: Outliers,
+    /// Summary statistics of the iteration durations in nanoseconds.
+    duration_summary: Summary,
+    /// Summary statistics of the iteration throughputs in tasks per second.
+    throughput_summary: Summary,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ThroughputMeasurement {
+    /// Total elapsed time.
+    #[serde(serialize_with = "as_nanos")]
+    pub elapsed: Duration,
+    /// Throughput in completed tasks per second.
+    pub throughput: f64,
+}
+
+impl ThroughputResult {
+    pub fn new(
+        measurements: Vec<ThroughputMeasurement>,
+        metrics: ActorMetrics,
+        allocations: AllocationResult,
+        outlier_method: OutlierMethod,
+    ) -> Self {
+        let durations = durations(&measurements);
+        Self {
+            outliers: Outliers::classify(&durations, outlier_method),
+            duration_summary: durations.iter().copied().collect(),
+            throughput_summary: measurements.iter().map(|m| m.throughput).collect(),
+            measurements,
+            metrics,
+            allocations,
+        }
+    }
+
+    /// Returns the summary statistics of the iteration durations in nanoseconds.
+    pub fn duration_summary(&self) -> Summary {
+        self.duration_summary
+    }
+
+    /// Returns the summary statistics of the iteration throughputs in tasks per second.
+    pub fn throughput_summary(&self) -> Summary {
+        self.throughput_summary
+    }
+
+    /// Returns the mean iteration duration, or zero if there are no iterations.
+    pub fn mean_duration(&self) -> Duration {
+        self.duration_summary().mean().map_or(Duration::ZERO, nanos)
+    }
+
+    /// Returns the mean throughput, or zero if there are no iterations.
+    pub fn mean_throughput(&self) -> f64 {
+        self.throughput_summary().mean().unwrap_or(0.0)
+    }
+
+    /// Returns the median iteration duration, or zero if there are no iterations.
+    pub fn median_duration(&self) -> Duration {
+        nanos(median(&durations(&self.measurements)))
+    }
+
+    /// Returns the median throughput, or zero if there are no iterations.
+    pub fn median_throughput(&self) -> f64 {
+        let throughputs = self
+            .measurements
+            .iter()
+            .map(|m| m.throughput)
+            .collect::<Vec<_>>();
+        median(&throughputs)
+    }
+
+    /// Returns the standard deviation of the iteration durations, or zero if there are less than
+    /// two iterations.
+    pub fn std_dev_duration(&self) -> Duration {
+        self.duration_summary()
+            .std_dev()
+            .map_or(Duration::ZERO, nanos)
+    }
+
+    /// Returns the coefficient of variation of the iteration durations, the standard deviation
+    /// relative to the mean, or zero if there are less than two iterations.
+    pub fn cv_duration(&self) -> f64 {
+        self.duration_summary().cv().unwrap_or(0.0)
+    }
+
+    /// Returns bootstrap confidence intervals of the mean and median iteration duration, in
+    /// nanoseconds, or `None` if there are less than two iterations.
+    pub fn duration_confidence_intervals(&self) -> Option<[ConfidenceInterval; 2]> {
+        let durations = durations(&self.measurements);
+        let mut resample = Vec::with_capacity(durations.len());
+
+        bootstrap(durations.len(), |indices| {
+            resample.clear();
+            resample.extend(indices.iter().map(|&i| durations[i]));
+            [mean(&resample), median(&resample)]
+        })
+    }
+
+    /// Returns the shortest iteration duration, or zero if there are no iterations.
+    pub fn min_duration(&self) -> Duration {
+        self.duration_summary().min().map_or(Duration::ZERO, nanos)
+    }
+
+    /// Returns the longest iteration duration, or zero if there are no iterations.
+    pub fn max_duration(&self) -> Duration {
+        self.duration_summary().max().map_or(Duration::ZERO, nanos)
+    }
+
+    /// Returns the lowest throughput, or zero if there are no iterations.
+    pub fn min_throughput(&self) -> f64 {
+        self.throughput_summary().min().unwrap_or(0.0)
+    }
+
+    /// Returns the highest throughput, or zero if there are no iterations.
+    pub fn max_throughput(&self) -> f64 {
+        self.throughput_summary().max().unwrap_or(0.0)
+    }
+}
+
+/// Returns the iteration durations in nanoseconds.
+fn durations(measurements: &[ThroughputMeasurement]) -> Vec<f64> {
+    measurements
+        .iter()
+        .map(|m| m.elapsed.as_nanos() as f64)
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct LatencyResult {
+    /// Latencies of all iterations.
+    pub histogram: Histogram,
+    /// Latencies per iteration.
+    pub iterations: Vec<Histogram>,
+    /// Outliers among the median latencies of the iterations.
+    pub outliers: Outliers,
+    /// Bootstrap confidence intervals of the mean, median and 99th percentile latency in
+    /// nanoseconds, from resampling the iterations. `None` if there are less than two iterations.
+    pub confidence_intervals: Option<[ConfidenceInterval; 3]>,
+}
+
+impl LatencyResult {
+    pub fn new(iterations: Vec<Histogram>, outlier_method: OutlierMethod) -> Self {
+        let mut histogram = Histogram::new();
+        for iteration in &iterations {
+            histogram.merge(iteration);
+        }
+
+        let medians = iterations
+            .iter()
+            .map(|iteration| iteration.quantile(0.5).as_nanos() as f64)
+            .collect::<Vec<_>>();
+
+        let mut resample = Histogram::new();
+        let confidence_intervals = bootstrap(iterations.len(), |indices| {
+            resample.clear();
+            for &i in indices {
+                resample.merge(&iterations[i]);
+            }
+            [
+                resample.mean().as_nanos() as f64,
+                resample.quantile(0.5).as_nanos() as f64,
+                resample.quantile(0.99).as_nanos() as f64,
+            ]
+        });
+
+        Self {
+            histogram,
+            outliers: Outliers::classify(&medians, outlier_method),
+            confidence_intervals,
+            iterations,
+        }
+    }
+
+    pub fn mean_latency(&self) -> Duration {
+        self.histogram.mean()
+    }
+
+    pub fn quantile(&self, quantile: f64) -> Duration {
+        self.histogram.quantile(quantile)
+    }
+
+    pub fn std_dev_latency(&self) -> Duration {
+        self.histogram.std_dev()
+    }
+
+    /// Returns the coefficient of variation of the latencies, the standard deviation relative to
+    /// the mean, or zero if there are less than two latencies.
+    pub fn cv_latency(&self) -> f64 {
+        self.histogram.summary().cv().unwrap_or(0.0)
+    }
+
+    pub fn min_latency(&self) -> Duration {
+        self.histogram.min()
+    }
+
+    pub fn max_latency(&self) -> Duration {
+        self.histogram.max()
+    }
+}
+
+#[derive(Debug)]
+pub struct LoadResult {
+    /// Metrics of the actor task over the whole run.
+    pub metrics: TaskMetrics,
+    /// Metrics of the actor task per iteration, including the warm-up.
+    pub intervals: Vec<TaskMetrics>,
+    /// Number of warm-up iterations at the start of `intervals`.
+    pub warmup: usize,
+    /// Outliers among the mean poll durations of the measured iterations.
+    pub outliers: Outliers,
+    /// Resource usage of the process per iteration. Empty if it can't be sampled on this platform.
+    pub resources: Vec<ResourceUsage>,
+    /// Metrics of the whole actor runtime, if enabled with `--runtime-metrics`.
+    pub runtime: Option<Box<RuntimeResult>>,
+}
+
+impl LoadResult {
+    /// Returns the metrics of the measured iterations, after the warm-up.
+    pub fn steady_state(&self) -> &[TaskMetrics] {
+        &self.intervals[self.warmup.min(self.intervals.len())..]
+    }
+
+    /// Returns the metrics of the actor task summed over the steady state, or `None` if there are
+    /// no measured iterations.
+    pub fn steady_state_metrics(&self) -> Option<TaskMetrics> {
+        let steady_state = self.steady_state();
+        if steady_state.is_empty() {
+            return None;
+        }
+
+        let mut metrics = TaskMetrics::default();
+        for interval in steady_state {
+            metrics.instrumented_count += interval.instrumented_count;
+            metrics.dropped_count += interval.dropped_count;
+            metrics.first_poll_count += interval.first_poll_count;
+            metrics.total_first_poll_delay += interval.total_first_poll_delay;
+            metrics.total_idled_count += interval.total_idled_count;
+            metrics.total_idle_duration += interval.total_idle_duration;
+            metrics.total_scheduled_count += interval.total_scheduled_count;
+            metrics.total_scheduled_duration += interval.total_scheduled_duration;
+            metrics.total_poll_count += interval.total_poll_count;
+            metrics.total_poll_duration += interval.total_poll_duration;
+            metrics.total_fast_poll_count += interval.total_fast_poll_count;
+            metrics.total_fast_poll_duration += interval.total_fast_poll_duration;
+            metrics.total_slow_poll_count += interval.total_slow_poll_count;
+            metrics.total_slow_poll_duration += interval.total_slow_poll_duration;
+            metrics.total_short_delay_count += interval.total_short_delay_count;
+            metrics.total_short_delay_duration += interval.total_short_delay_duration;
+            metrics.total_long_delay_count += interval.total_long_delay_count;
+            metrics.total_long_delay_duration += interval.total_long_delay_duration;
+        }
+        Some(metrics)
+    }
+
+    /// Returns the load of every steady-state iteration.
+    pub fn steady_state_loads(&self) -> Vec<f64> {
+        self.steady_state().iter().map(calculate_load).collect()
+    }
+
+    /// Returns the standard deviation of the mean poll duration of the steady-state iterations.
+    pub fn poll_duration_std_dev(&self) -> Option<Duration> {
+        self.steady_state()
+            .iter()
+            .map(|interval| interval.mean_poll_duration().as_nanos() as f64)
+            .collect::<Summary>()
+            .std_dev()
+            .map(nanos)
+    }
+
+    /// Returns the standard deviation of the slow poll ratio of the steady-state iterations.
+    pub fn slow_poll_ratio_std_dev(&self) -> Option<f64> {
+        self.steady_state()
+            .iter()
+            .map(TaskMetrics::slow_poll_ratio)
+            .collect::<Summary>()
+            .std_dev()
+    }
+
+    /// Returns the CPU time of the process as a fraction of the wall-clock time. This can be
+    /// more than 100% if multiple threads are busy.
+    pub fn cpu_utilization(&self) -> Option<f64> {
+        if self.resources.is_empty() {
+            return None;
+        }
+
+        let cpu_time = self
+            .resources
+            .iter()
+            .map(|usage| usage.user_time + usage.system_time)
+            .sum::<Duration>();
+        let elapsed = self
+            .resources
+            .iter()
+            .map(|usage| usage.elapsed)
+            .sum::<Duration>();
+        Some(cpu_time.as_secs_f64() / elapsed.as_secs_f64())
+    }
+
+    pub fn mean_user_time(&self) -> Option<Duration> {
+        self.mean_duration(|usage| usage.user_time)
+    }
+
+    pub fn mean_system_time(&self) -> Option<Duration> {
+        self.mean_duration(|usage| usage.system_time)
+    }
+
+    pub fn peak_rss_bytes(&self) -> Option<u64> {
+        self.resources
+            .iter()
+            .map(|usage| usage.peak_rss_bytes)
+            .max()
+    }
+
+    pub fn mean_voluntary_context_switches(&self) -> Option<u64> {
+        self.mean_count(|usage| usage.voluntary_context_switches)
+    }
+
+    pub fn mean_involuntary_context_switches(&self) -> Option<u64> {
+        self.mean_count(|usage| usage.involuntary_context_switches)
+    }
+
+    fn mean_duration(&self, value: impl Fn(&ResourceUsage) -> Duration) -> Option<Duration> {
+        self.resources
+            .iter()
+            .map(|usage| value(usage).as_nanos() as f64)
+            .collect::<Summary>()
+            .mean()
+            .map(nanos)
+    }
+
+    fn mean_count(&self, value: impl Fn(&ResourceUsage) -> u64) -> Option<u64> {
+        self.resources
+            .iter()
+            .map(|usage| value(usage) as f64)
+            .collect::<Summary>()
+            .mean()
+            .map(|mean| mean.round() as u64)
+    }
+}
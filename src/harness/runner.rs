@@ -0,0 +1,209 @@
+use tabled::{
+    Table,
+    settings::{Color, object::Rows},
+};
+
+use super::{
+    Config, LoadResult, Registry, Report, RuntimeConfig, Scenario, ScenarioResult, SummaryRow,
+    ToRow, comparison_table,
+};
+
+/// Runs the selected [`Scenario`]s for the selected actors in a [`Registry`] on every runtime
+/// configuration, prints the results, and adds them to a [`Report`].
+pub struct Runner<'a> {
+    config: &'a Config,
+}
+
+impl<'a> Runner<'a> {
+    pub fn new(config: &'a Config) -> Self {
+        Self { config }
+    }
+
+    /// Runs all selected scenarios, and finally prints a summary table with the headline numbers
+    /// of every actor on every runtime configuration, if more than one scenario was run.
+    pub fn run(&self, registry: &Registry, report: &mut Report) {
+        let mut summary = Vec::new();
+        for scenario in &self.config.scenarios {
+            self.run_scenario_into(registry, *scenario, report, &mut summary);
+        }
+
+        if self.config.scenarios.len() > 1 && !summary.is_empty() {
+            let mut table = Table::new(
+                summary
+                    .into_iter()
+                    .map(|(runtime, row): (&RuntimeConfig, _)| (runtime.columns(), row)),
+            );
+            self.config.print_table(&mut table);
+            report.add_table("Summary", &table);
+        }
+    }
+
+    /// Runs `scenario` for every selected actor on every runtime configuration. Every result is
+    /// printed as soon as it is available, followed by a table that compares all actors and
+    /// runtimes.
+    pub fn run_scenario(&self, registry: &Registry, scenario: Scenario, report: &mut Report) {
+        self.run_scenario_into(registry, scenario, report, &mut Vec::new());
+    }
+
+    /// Runs `scenario` like [`Runner::run_scenario`], and adds the headline numbers of every
+    /// result to the row of its actor and runtime in `summary`.
+    fn run_scenario_into(
+        &self,
+        registry: &Registry,
+        scenario: Scenario,
+        report: &mut Report,
+        summary: &mut Vec<(&'a RuntimeConfig, SummaryRow)>,
+    ) {
+        let mut results = Vec::new();
+
+        for runtime in &self.config.runtimes {
+            let runtimes = runtime.build();
+            let columns = runtime.columns();
+
+            for actor in registry.actors() {
+                let name = actor.name();
+                if !self.config.is_selected(name, scenario) {
+                    continue;
+                }
+
+                let result = actor.run(&runtimes, scenario, self.config);
+
+                let mut table = match &result {
+                    ScenarioResult::Throughput(result) | ScenarioResult::SlowConsumer(result) => {
+                        Table::new([(columns.clone(), result.to_row(name))])
+                    }
+                    ScenarioResult::Latency(result) => {
+                        Table::new([(columns.clone(), result.to_row(name))])
+                    }
+                    ScenarioResult::Load(result) => {
+                        Table::new([(columns.clone(), result.to_row(name))])
+                    }
+                    ScenarioResult::OpenLoop(result) => Table::new(
+                        result
+                            .to_row(name)
+                            .into_iter()
+                            .map(|row| (columns.clone(), row)),
+                    ),
+                };
+                self.config.print_table(&mut table);
+
+                if let ScenarioResult::Load(LoadResult {
+                    runtime: Some(runtime_result),
+                    ..
+                }) = &result
+                {
+                    let mut table = Table::new([(columns.clone(), runtime_result.to_row(name))]);
+                    self.config.print_table(&mut table);
+                }
+
+                results.push((runtime, name, result));
+            }
+        }
+
+        if results.is_empty() {
+            return;
+        }
+
+        let (title, mut table) = match scenario {
+            Scenario::Throughput | Scenario::SlowConsumer => {
+                let rows = results
+                    .iter()
+                    .filter_map(|(runtime, name, result)| match result {
+                        ScenarioResult::Throughput(throughput)
+                        | ScenarioResult::SlowConsumer(throughput) => {
+                            Some((runtime.columns(), throughput.to_row(name), result.samples()))
+                        }
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                let title = match scenario {
+                    Scenario::Throughput => "Throughput",
+                    _ => "Slow consumer",
+                };
+                (title, comparison_table(rows))
+            }
+            Scenario::Latency => {
+                let rows = results
+                    .iter()
+                    .filter_map(|(runtime, name, result)| match result {
+                        ScenarioResult::Latency(latency) => {
+                            Some((runtime.columns(), latency.to_row(name), result.samples()))
+                        }
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                ("Latency", comparison_table(rows))
+            }
+            Scenario::Load => {
+                let rows = results
+                    .iter()
+                    .filter_map(|(runtime, name, result)| match result {
+                        ScenarioResult::Load(load) => {
+                            Some((runtime.columns(), load.to_row(name), result.samples()))
+                        }
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                ("Load (CPU)", comparison_table(rows))
+            }
+            Scenario::OpenLoop => {
+                let mut rows = results
+                    .iter()
+                    .filter_map(|(runtime, name, result)| match result {
+                        ScenarioResult::OpenLoop(result) => Some(
+                            result
+                                .to_row(name)
+                                .into_iter()
+                                .map(|row| (runtime.columns(), row)),
+                        ),
+                        _ => None,
+                    })
+                    .flatten()
+                    .collect::<Vec<_>>();
+                rows.sort_by(|(_, a), (_, b)| {
+                    a.offered_rate
+                        .total_cmp(&b.offered_rate)
+                        .then(a.p99_latency.cmp(&b.p99_latency))
+                });
+                ("Open loop", Table::new(rows))
+            }
+        };
+
+        self.config.print_table(&mut table);
+        report.add_table(title, &table);
+
+        let mut runtime_rows = results
+            .iter()
+            .filter_map(|(runtime, name, result)| match result {
+                ScenarioResult::Load(LoadResult {
+                    runtime: Some(runtime_result),
+                    ..
+                }) => Some((runtime.columns(), runtime_result.to_row(name))),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if !runtime_rows.is_empty() {
+            runtime_rows.sort_by(|(_, a), (_, b)| a.busy_ratio.total_cmp(&b.busy_ratio));
+            let mut table = Table::new(runtime_rows);
+            table.modify(Rows::one(1), Color::BOLD);
+            self.config.print_table(&mut table);
+            report.add_table("Runtime metrics", &table);
+        }
+
+        for (runtime, name, result) in results {
+            let row = match summary
+                .iter_mut()
+                .position(|(other, row)| *other == runtime && row.actor_type == name)
+            {
+                Some(i) => &mut summary[i].1,
+                None => {
+                    summary.push((runtime, SummaryRow::new(name)));
+                    &mut summary.last_mut().unwrap().1
+                }
+            };
+            row.add(&result);
+
+            report.add(&runtime.to_string(), name, result);
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use std::fmt;
+
+use serde::Serialize;
+use tabled::Tabled;
+use tokio::runtime::{Builder, Runtime};
+
+use super::config::parse_positive;
+
+/// The flavor of a tokio runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Flavor {
+    CurrentThread,
+    /// A multi-thread runtime with `workers` worker threads, or one per CPU if `None`.
+    MultiThread {
+        workers: Option<usize>,
+    },
+}
+
+/// The configuration of the runtime an actor runs on.
+///
+/// Written as a comma-separated list of a flavor and options, e.g.
+/// `multi-thread:4,no-lifo,event-interval=31,separate-producer`:
+/// - `current-thread`, `multi-thread` or `multi-thread:<WORKERS>`
+/// - `no-lifo`: disable the LIFO slot of the multi-thread scheduler
+/// - `event-interval=<N>`: poll for external events every N scheduler ticks
+/// - `global-queue-interval=<N>`: poll the global task queue every N scheduler ticks
+/// - `separate-producer`: send the tasks from a separate single-worker runtime, instead of the
+///   runtime of the actor
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuntimeConfig {
+    pub flavor: Flavor,
+    pub lifo_slot: bool,
+    pub event_interval: Option<u32>,
+    pub global_queue_interval: Option<u32>,
+    pub separate_producer: bool,
+}
+
+impl RuntimeConfig {
+    pub fn current_thread() -> Self {
+        Self {
+            flavor: Flavor::CurrentThread,
+            lifo_slot: true,
+            event_interval: None,
+            global_queue_interval: None,
+            separate_producer: false,
+        }
+    }
+
+    pub fn multi_thread() -> Self {
+        Self {
+            flavor: Flavor::MultiThread { workers: None },
+            ..Self::current_thread()
+        }
+    }
+
+    /// The runtimes of `--runtime matrix`: both flavors with a shared and a separate producer,
+    /// and the multi-thread runtime without LIFO slot (if available) and with more frequent event
+    /// and global queue polling than the defaults.
+    pub fn matrix() -> Vec<Self> {
+        let mut matrix = vec![
+            Self::current_thread(),
+            Self {
+                separate_producer: true,
+                ..Self::current_thread()
+            },
+            Self::multi_thread(),
+            Self {
+                separate_producer: true,
+                ..Self::multi_thread()
+            },
+            Self {
+                event_interval: Some(7),
+                global_queue_interval: Some(7),
+                ..Self::multi_thread()
+            },
+        ];
+
+        if cfg!(tokio_unstable) {
+            matrix.push(Self {
+                lifo_slot: false,
+                ..Self::multi_thread()
+            });
+        }
+
+        matrix
+    }
+
+    /// Parses a runtime configuration, see [`RuntimeConfig`].
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut parts = value.split(',');
+        let flavor = parts.next().unwrap_or_default();
+
+        let mut config = match flavor.split_once(':') {
+            None if flavor == "current-thread" => Self::current_thread(),
+            None if flavor == "multi-thread" => Self::multi_thread(),
+            Some(("multi-thread", workers)) => Self {
+                flavor: Flavor::MultiThread {
+                    workers: Some(parse_positive(workers)?),
+                },
+                ..Self::current_thread()
+            },
+            _ => return Err(format!("unknown runtime `{value}`")),
+        };
+
+        for option in parts {
+            match option.split_once('=') {
+                None if option == "no-lifo" => {
+                    if !cfg!(tokio_unstable) {
+                        return Err("`no-lifo` requires `--cfg tokio_unstable`".to_string());
+                    }
+                    config.lifo_slot = false;
+                }
+                None if option == "separate-producer" => config.separate_producer = true,
+                Some(("event-interval", n)) => config.event_interval = Some(parse_positive(n)?),
+                Some(("global-queue-interval", n)) => {
+                    config.global_queue_interval = Some(parse_positive(n)?)
+                }
+                _ => return Err(format!("unknown runtime option `{option}`")),
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Returns the number of worker threads of the actor runtime.
+    pub fn workers(&self) -> usize {
+        match self.flavor {
+            Flavor::CurrentThread => 1,
+            Flavor::MultiThread { workers } => workers.unwrap_or_else(|| {
+                std::thread::available_parallelism().map_or(1, |workers| workers.get())
+            }),
+        }
+    }
+
+    /// Builds the runtime of the actor, and the runtime of the producer if it is separate.
+    pub fn build(&self) -> Runtimes {
+        let mut builder = match self.flavor {
+            Flavor::CurrentThread => Builder::new_current_thread(),
+            Flavor::MultiThread { .. } => {
+                let mut builder = Builder::new_multi_thread();
+                builder.worker_threads(self.workers());
+                builder
+            }
+        };
+
+        builder.enable_time();
+        #[cfg(tokio_unstable)]
+        if !self.lifo_slot {
+            builder.disable_lifo_slot();
+        }
+        if let Some(event_interval) = self.event_interval {
+            builder.event_interval(event_interval);
+        }
+        if let Some(global_queue_interval) = self.global_queue_interval {
+            builder.global_queue_interval(global_queue_interval);
+        }
+
+        let producer = self.separate_producer.then(|| {
+            Builder::new_multi_thread()
+                .worker_threads(1)
+                .thread_name("producer")
+                .enable_time()
+                .build()
+                .unwrap()
+        });
+
+        Runtimes {
+            actor: builder.build().unwrap(),
+            producer,
+        }
+    }
+
+    /// Returns the table columns that describe this configuration.
+    pub fn columns(&self) -> RuntimeColumns {
+        RuntimeColumns {
+            runtime: match self.flavor {
+                Flavor::CurrentThread => "current-thread",
+                Flavor::MultiThread { .. } => "multi-thread",
+            },
+            workers: self.workers(),
+            lifo_slot: self.lifo_slot,
+            event_interval: self.event_interval,
+            global_queue_interval: self.global_queue_interval,
+            separate_producer: self.separate_producer,
+        }
+    }
+}
+
+impl fmt::Display for RuntimeConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.flavor {
+            Flavor::CurrentThread => f.write_str("current-thread")?,
+            Flavor::MultiThread { .. } => write!(f, "multi-thread:{}", self.workers())?,
+        }
+        if !self.lifo_slot {
+            f.write_str(",no-lifo")?;
+        }
+        if let Some(event_interval) = self.event_interval {
+            write!(f, ",event-interval={event_interval}")?;
+        }
+        if let Some(global_queue_interval) = self.global_queue_interval {
+            write!(f, ",global-queue-interval={global_queue_interval}")?;
+        }
+        if self.separate_producer {
+            f.write_str(",separate-producer")?;
+        }
+        Ok(())
+    }
+}
+
+/// The runtimes built from a [`RuntimeConfig`].
+pub struct Runtimes {
+    /// The runtime the actor runs on.
+    pub actor: Runtime,
+    /// The runtime the tasks are sent from. If `None`, they are sent from the actor runtime.
+    pub producer: Option<Runtime>,
+}
+
+impl Runtimes {
+    /// Returns the runtime the tasks are sent from.
+    pub fn producer(&self) -> &Runtime {
+        self.producer.as_ref().unwrap_or(&self.actor)
+    }
+}
+
+/// The columns that are prepended to every table, to tell the runtime configurations apart.
+#[derive(Debug, Tabled, Clone)]
+pub struct RuntimeColumns {
+    pub runtime: &'static str,
+    pub workers: usize,
+    #[tabled(rename = "lifo", display = "format_on_off")]
+    pub lifo_slot: bool,
+    #[tabled(display = "format_interval")]
+    pub event_interval: Option<u32>,
+    #[tabled(display = "format_interval")]
+    pub global_queue_interval: Option<u32>,
+    #[tabled(rename = "producer", display = "format_producer")]
+    pub separate_producer: bool,
+}
+
+fn format_on_off(value: &bool) -> String {
+    if *value { "on" } else { "off" }.to_string()
+}
+
+fn format_interval(interval: &Option<u32>) -> String {
+    interval.map_or("default".to_string(), |interval| interval.to_string())
+}
+
+fn format_producer(separate: &bool) -> String {
+    if *separate { "separate" } else { "shared" }.to_string()
+}
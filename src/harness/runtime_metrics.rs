@@ -0,0 +1,120 @@
+use std::time::Duration;
+#[cfg(tokio_unstable)]
+use std::{sync::mpsc, thread::JoinHandle};
+
+#[cfg(tokio_unstable)]
+use tokio::runtime::Handle;
+#[cfg(tokio_unstable)]
+use tokio_metrics::{RuntimeMetrics, RuntimeMonitor};
+
+/// How often the sampler takes a [`RuntimeMonitor`] interval, to catch the queue depths, which
+/// are only known at the time of sampling.
+#[cfg(tokio_unstable)]
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Runtime-wide metrics of a whole run, aggregated from the intervals of a
+/// [`tokio_metrics::RuntimeMonitor`]. Only available with `--cfg tokio_unstable`.
+#[derive(Debug, Clone, Default)]
+pub struct RuntimeResult {
+    /// Number of worker threads of the runtime.
+    pub workers: usize,
+    pub elapsed: Duration,
+    /// Time the workers spent busy, summed over all workers.
+    pub total_busy_duration: Duration,
+    /// Number of tasks polled by the workers.
+    pub total_polls_count: u64,
+    /// Number of times the workers parked.
+    pub total_park_count: u64,
+    /// Number of times the workers unparked without finding new work.
+    pub total_noop_count: u64,
+    /// Number of tasks the workers stole from other workers.
+    pub total_steal_count: u64,
+    /// Number of times the workers stole tasks from other workers.
+    pub total_steal_operations: u64,
+    /// Number of tasks scheduled from outside of the runtime.
+    pub num_remote_schedules: u64,
+    /// Number of tasks scheduled on the local queue of a worker.
+    pub total_local_schedule_count: u64,
+    /// Number of times a local queue overflowed into the global queue.
+    pub total_overflow_count: u64,
+    /// Number of times a task was forced to yield because it ran out of cooperative budget.
+    pub budget_forced_yield_count: u64,
+    /// Largest depth of the global queue seen while sampling.
+    pub max_global_queue_depth: usize,
+    /// Largest depth of a local queue seen while sampling.
+    pub max_local_queue_depth: usize,
+}
+
+impl RuntimeResult {
+    /// Returns the fraction of time the workers were busy, on average over all workers.
+    pub fn busy_ratio(&self) -> f64 {
+        let capacity = self.elapsed.as_secs_f64() * self.workers as f64;
+        if capacity == 0.0 {
+            return 0.0;
+        }
+
+        self.total_busy_duration.as_secs_f64() / capacity
+    }
+
+    #[cfg(tokio_unstable)]
+    fn add(&mut self, interval: &RuntimeMetrics) {
+        self.workers = interval.workers_count;
+        self.elapsed += interval.elapsed;
+        self.total_busy_duration += interval.total_busy_duration;
+        self.total_polls_count += interval.total_polls_count;
+        self.total_park_count += interval.total_park_count;
+        self.total_noop_count += interval.total_noop_count;
+        self.total_steal_count += interval.total_steal_count;
+        self.total_steal_operations += interval.total_steal_operations;
+        self.num_remote_schedules += interval.num_remote_schedules;
+        self.total_local_schedule_count += interval.total_local_schedule_count;
+        self.total_overflow_count += interval.total_overflow_count;
+        self.budget_forced_yield_count += interval.budget_forced_yield_count;
+        self.max_global_queue_depth = self.max_global_queue_depth.max(interval.global_queue_depth);
+        self.max_local_queue_depth = self
+            .max_local_queue_depth
+            .max(interval.max_local_queue_depth);
+    }
+}
+
+/// Samples the intervals of a [`RuntimeMonitor`] on a background thread, from [`start`] until
+/// [`finish`].
+///
+/// [`start`]: RuntimeSampler::start
+/// [`finish`]: RuntimeSampler::finish
+#[cfg(tokio_unstable)]
+pub struct RuntimeSampler {
+    stop: mpsc::Sender<()>,
+    thread: JoinHandle<RuntimeResult>,
+}
+
+#[cfg(tokio_unstable)]
+impl RuntimeSampler {
+    pub fn start(handle: &Handle) -> Self {
+        let mut intervals = RuntimeMonitor::new(handle).intervals();
+        let (stop, stopped) = mpsc::channel();
+
+        let thread = std::thread::Builder::new()
+            .name("runtime-sampler".to_string())
+            .spawn(move || {
+                let mut result = RuntimeResult::default();
+                // Stops when `stop` is dropped, after taking the last interval.
+                loop {
+                    let timeout = stopped.recv_timeout(SAMPLE_INTERVAL);
+                    result.add(&intervals.next().unwrap());
+                    if let Err(mpsc::RecvTimeoutError::Disconnected) = timeout {
+                        return result;
+                    }
+                }
+            })
+            .unwrap();
+
+        Self { stop, thread }
+    }
+
+    /// Stops sampling and returns the metrics since [`RuntimeSampler::start`].
+    pub fn finish(self) -> RuntimeResult {
+        drop(self.stop);
+        self.thread.join().unwrap()
+    }
+}
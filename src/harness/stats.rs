@@ -0,0 +1,602 @@
+use std::fmt;
+
+use serde::Serialize;
+
+/// Returns the two-sided p-value of the Mann-Whitney U test, the probability that samples as
+/// different as `a` and `b` are drawn from the same distribution.
+///
+/// Uses the normal approximation with a tie correction, which is reasonable from about 8 samples
+/// per group. Returns `1.0` if either group is empty or all samples are equal.
+pub fn mann_whitney_u(a: &[f64], b: &[f64]) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 1.0;
+    }
+
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let n = n1 + n2;
+
+    let mut samples = a
+        .iter()
+        .map(|value| (*value, true))
+        .chain(b.iter().map(|value| (*value, false)))
+        .collect::<Vec<_>>();
+    samples.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    // Assign average ranks to ties, and sum the ranks of `a`.
+    let mut rank_sum = 0.0;
+    let mut tie_correction = 0.0;
+    let mut i = 0;
+    while i < samples.len() {
+        let mut j = i;
+        while j < samples.len() && samples[j].0 == samples[i].0 {
+            j += 1;
+        }
+
+        let ties = (j - i) as f64;
+        let rank = (i + j + 1) as f64 / 2.0;
+        rank_sum += rank * samples[i..j].iter().filter(|(_, in_a)| *in_a).count() as f64;
+        tie_correction += ties.powi(3) - ties;
+        i = j;
+    }
+
+    let u = rank_sum - n1 * (n1 + 1.0) / 2.0;
+    let mean = n1 * n2 / 2.0;
+    let variance = n1 * n2 / 12.0 * ((n + 1.0) - tie_correction / (n * (n - 1.0)));
+    if variance <= 0.0 {
+        return 1.0;
+    }
+
+    // Continuity correction towards the mean.
+    let z = ((u - mean).abs() - 0.5).max(0.0) / variance.sqrt();
+    (2.0 * (1.0 - normal_cdf(z))).clamp(0.0, 1.0)
+}
+
+/// The number of resamples of [`bootstrap`].
+pub const BOOTSTRAP_RESAMPLES: usize = 1000;
+
+/// The confidence level of the confidence intervals of [`bootstrap`].
+pub const CONFIDENCE_LEVEL: f64 = 0.95;
+
+/// A confidence interval at [`CONFIDENCE_LEVEL`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ConfidenceInterval {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+/// Returns percentile bootstrap confidence intervals of statistics of `n` samples, or `None` if
+/// there are less than two samples.
+///
+/// `statistics` computes the statistics of a resample, given as the indices of the samples it
+/// contains, so that samples can be anything from numbers to histograms. The random number
+/// generator is seeded, so the intervals are the same for the same samples.
+pub fn bootstrap<const N: usize>(
+    n: usize,
+    mut statistics: impl FnMut(&[usize]) -> [f64; N],
+) -> Option<[ConfidenceInterval; N]> {
+    if n < 2 {
+        return None;
+    }
+
+    let mut rng = fastrand::Rng::with_seed(0x5eed);
+    let mut indices = vec![0; n];
+    let mut resamples = [(); N].map(|_| Vec::with_capacity(BOOTSTRAP_RESAMPLES));
+
+    for _ in 0..BOOTSTRAP_RESAMPLES {
+        indices.fill_with(|| rng.usize(..n));
+        for (resamples, statistic) in resamples.iter_mut().zip(statistics(&indices)) {
+            resamples.push(statistic);
+        }
+    }
+
+    let alpha = 1.0 - CONFIDENCE_LEVEL;
+    Some(resamples.map(|mut resamples| {
+        resamples.sort_by(f64::total_cmp);
+        ConfidenceInterval {
+            lower: quantile(&resamples, alpha / 2.0),
+            upper: quantile(&resamples, 1.0 - alpha / 2.0),
+        }
+    }))
+}
+
+/// Streaming summary statistics of a sequence of values: the count, mean, variance, minimum and
+/// maximum, updated one value at a time in constant memory.
+///
+/// The mean and variance are computed with Welford's algorithm, which doesn't accumulate a sum
+/// that can overflow or lose precision over long runs. Summaries of separate runs can be merged.
+/// Every statistic is `None` if there are not enough values for it, so empty runs are explicit.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Summary {
+    count: u64,
+    mean: f64,
+    /// Sum of squared differences from the mean.
+    m2: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Summary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, value: f64) {
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    /// Adds all values of `other` to this summary (Chan et al.).
+    pub fn merge(&mut self, other: &Summary) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        let (n1, n2, n) = (self.count as f64, other.count as f64, count as f64);
+
+        self.mean += delta * n2 / n;
+        self.m2 += other.m2 + delta * delta * n1 * n2 / n;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.count = count;
+    }
+
+    /// Returns the number of values.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the mean, or `None` if there are no values.
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.mean)
+    }
+
+    /// Returns the sample variance, or `None` if there are less than two values.
+    pub fn variance(&self) -> Option<f64> {
+        (self.count > 1).then(|| self.m2.max(0.0) / (self.count - 1) as f64)
+    }
+
+    /// Returns the sample standard deviation, or `None` if there are less than two values.
+    pub fn std_dev(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+
+    /// Returns the coefficient of variation, the standard deviation relative to the mean, or
+    /// `None` if there are less than two values or the mean is zero.
+    pub fn cv(&self) -> Option<f64> {
+        let std_dev = self.std_dev()?;
+        (self.mean != 0.0).then(|| std_dev / self.mean)
+    }
+
+    /// Returns the smallest value, or `None` if there are no values.
+    pub fn min(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    /// Returns the largest value, or `None` if there are no values.
+    pub fn max(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.max)
+    }
+}
+
+impl Extend<f64> for Summary {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, values: I) {
+        for value in values {
+            self.add(value);
+        }
+    }
+}
+
+impl FromIterator<f64> for Summary {
+    fn from_iter<I: IntoIterator<Item = f64>>(values: I) -> Self {
+        let mut summary = Self::new();
+        summary.extend(values);
+        summary
+    }
+}
+
+/// Returns the mean of `values`, or `0.0` if there are none.
+pub fn mean(values: &[f64]) -> f64 {
+    values
+        .iter()
+        .copied()
+        .collect::<Summary>()
+        .mean()
+        .unwrap_or(0.0)
+}
+
+/// Returns the median of `values`, or `0.0` if there are none.
+pub fn median(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    quantile(&sorted, 0.5)
+}
+
+/// Returns the sample standard deviation of `values`, or `0.0` if there are less than two.
+pub fn std_dev(values: &[f64]) -> f64 {
+    values
+        .iter()
+        .copied()
+        .collect::<Summary>()
+        .std_dev()
+        .unwrap_or(0.0)
+}
+
+/// The cumulative distribution function of the standard normal distribution.
+pub fn normal_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+}
+
+/// Approximation of the error function, with a maximum error of 1.5e-7 (Abramowitz and Stegun,
+/// 7.1.26).
+fn erf(x: f64) -> f64 {
+    let sign = x.signum();
+    let x = x.abs();
+
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let polynomial = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+
+    sign * (1.0 - polynomial * (-x * x).exp())
+}
+
+/// Returns the `quantile` of `sorted` with linear interpolation between the closest ranks.
+/// `sorted` must be sorted in increasing order and not be empty.
+pub fn quantile(sorted: &[f64], quantile: f64) -> f64 {
+    let rank = quantile.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+/// How iteration results are classified as outliers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutlierMethod {
+    /// Tukey's fences: mild outliers are more than 1.5 interquartile ranges outside the
+    /// quartiles, severe outliers more than 3.
+    #[default]
+    Tukey,
+    /// Modified z-scores based on the median absolute deviation (MAD): mild outliers have a score
+    /// above 3.5, severe outliers above 7. More robust than Tukey's fences if many iterations are
+    /// off.
+    Mad,
+}
+
+impl OutlierMethod {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "tukey" => Ok(OutlierMethod::Tukey),
+            "mad" => Ok(OutlierMethod::Mad),
+            _ => Err(format!("unknown outlier method `{value}`")),
+        }
+    }
+}
+
+impl fmt::Display for OutlierMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OutlierMethod::Tukey => "tukey",
+            OutlierMethod::Mad => "mad",
+        })
+    }
+}
+
+/// The number of outliers among the iteration results of a benchmark case, by side and severity.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Outliers {
+    pub low_severe: usize,
+    pub low_mild: usize,
+    pub high_mild: usize,
+    pub high_severe: usize,
+}
+
+impl Outliers {
+    /// Classifies `values` with `method`. Needs at least 4 values, and values that are not all
+    /// the same, otherwise no value is an outlier.
+    pub fn classify(values: &[f64], method: OutlierMethod) -> Self {
+        let mut outliers = Self::default();
+        if values.len() < 4 {
+            return outliers;
+        }
+
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        // The fences below or above which a value is a severe or mild outlier.
+        let fences = match method {
+            OutlierMethod::Tukey => {
+                let (q1, q3) = (quantile(&sorted, 0.25), quantile(&sorted, 0.75));
+                let iqr = q3 - q1;
+                [
+                    q1 - 3.0 * iqr,
+                    q1 - 1.5 * iqr,
+                    q3 + 1.5 * iqr,
+                    q3 + 3.0 * iqr,
+                ]
+            }
+            OutlierMethod::Mad => {
+                let median = quantile(&sorted, 0.5);
+                let mut deviations = sorted
+                    .iter()
+                    .map(|value| (value - median).abs())
+                    .collect::<Vec<_>>();
+                deviations.sort_by(f64::total_cmp);
+                // 0.6745 is the 75th percentile of the standard normal distribution, so the
+                // scores are comparable to z-scores for normally distributed values.
+                let spread = quantile(&deviations, 0.5) / 0.6745;
+                if spread == 0.0 {
+                    return outliers;
+                }
+                [
+                    median - 7.0 * spread,
+                    median - 3.5 * spread,
+                    median + 3.5 * spread,
+                    median + 7.0 * spread,
+                ]
+            }
+        };
+
+        for value in sorted {
+            if value < fences[0] {
+                outliers.low_severe += 1;
+            } else if value < fences[1] {
+                outliers.low_mild += 1;
+            } else if value > fences[3] {
+                outliers.high_severe += 1;
+            } else if value > fences[2] {
+                outliers.high_mild += 1;
+            }
+        }
+
+        outliers
+    }
+
+    pub fn total(&self) -> usize {
+        self.low_severe + self.low_mild + self.high_mild + self.high_severe
+    }
+
+    pub fn severe(&self) -> usize {
+        self.low_severe + self.high_severe
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() <= 1e-9 * expected.abs().max(1.0),
+            "{actual} != {expected}"
+        );
+    }
+
+    #[test]
+    fn empty_summary_has_no_statistics() {
+        let summary = Summary::new();
+        assert!(summary.is_empty());
+        assert_eq!(summary.count(), 0);
+        assert_eq!(summary.mean(), None);
+        assert_eq!(summary.variance(), None);
+        assert_eq!(summary.std_dev(), None);
+        assert_eq!(summary.cv(), None);
+        assert_eq!(summary.min(), None);
+        assert_eq!(summary.max(), None);
+    }
+
+    #[test]
+    fn single_value_has_no_spread() {
+        let summary = [-3.0].into_iter().collect::<Summary>();
+        assert_eq!(summary.count(), 1);
+        assert_eq!(summary.mean(), Some(-3.0));
+        assert_eq!(summary.std_dev(), None);
+        assert_eq!(summary.min(), Some(-3.0));
+        assert_eq!(summary.max(), Some(-3.0));
+    }
+
+    #[test]
+    fn summary_matches_two_pass_statistics() {
+        let values = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        let summary = values.into_iter().collect::<Summary>();
+
+        assert_eq!(summary.count(), 8);
+        assert_close(summary.mean().unwrap(), 5.0);
+        assert_close(summary.variance().unwrap(), 32.0 / 7.0);
+        assert_close(summary.cv().unwrap(), (32.0f64 / 7.0).sqrt() / 5.0);
+        assert_eq!(summary.min(), Some(2.0));
+        assert_eq!(summary.max(), Some(9.0));
+    }
+
+    #[test]
+    fn summary_is_stable_for_large_offsets() {
+        // A naive sum of squares loses all precision here.
+        let values = [1e9 + 4.0, 1e9 + 7.0, 1e9 + 13.0, 1e9 + 16.0];
+        let summary = values.into_iter().collect::<Summary>();
+
+        assert_close(summary.mean().unwrap(), 1e9 + 10.0);
+        assert_close(summary.variance().unwrap(), 30.0);
+    }
+
+    #[test]
+    fn summary_of_durations_does_not_overflow() {
+        // The sum of these nanoseconds overflows a `u64`.
+        let summary = (0..1000).map(|_| u64::MAX as f64).collect::<Summary>();
+        assert_close(summary.mean().unwrap(), u64::MAX as f64);
+        assert_close(summary.std_dev().unwrap(), 0.0);
+    }
+
+    #[test]
+    fn merged_summaries_match_a_single_summary() {
+        let values = (0..100).map(|i| (i * i % 37) as f64).collect::<Vec<_>>();
+        let expected = values.iter().copied().collect::<Summary>();
+
+        let mut merged = values[..30].iter().copied().collect::<Summary>();
+        merged.merge(&values[30..].iter().copied().collect());
+        merged.merge(&Summary::new());
+
+        assert_eq!(merged.count(), expected.count());
+        assert_close(merged.mean().unwrap(), expected.mean().unwrap());
+        assert_close(merged.variance().unwrap(), expected.variance().unwrap());
+        assert_eq!(merged.min(), expected.min());
+        assert_eq!(merged.max(), expected.max());
+
+        let mut empty = Summary::new();
+        empty.merge(&expected);
+        assert_eq!(empty, expected);
+    }
+
+    #[test]
+    fn identical_samples_are_not_significant() {
+        let samples = [3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0, 5.0, 3.0];
+        assert!(mann_whitney_u(&samples, &samples) > 0.9);
+    }
+
+    #[test]
+    fn separated_samples_are_significant() {
+        let low = (0..10).map(f64::from).collect::<Vec<_>>();
+        let high = (10..20).map(f64::from).collect::<Vec<_>>();
+
+        // U = 0, so z = (50 - 0.5) / sqrt(175).
+        let p_value = mann_whitney_u(&low, &high);
+        assert!(p_value < 0.001, "{p_value}");
+        assert_close(mann_whitney_u(&high, &low), p_value);
+    }
+
+    #[test]
+    fn ties_get_average_ranks() {
+        // The ranks of `a` are 1.5, 1.5, 4.5 and 4.5, so U = 2, and the tie correction lowers
+        // the variance to 16 / 12 * (9 - 72 / 56).
+        let p_value = mann_whitney_u(&[1.0, 1.0, 2.0, 2.0], &[2.0, 2.0, 3.0, 3.0]);
+        assert!((p_value - 0.08636).abs() < 1e-4, "{p_value}");
+
+        // Without any spread, there is nothing to test.
+        assert_eq!(mann_whitney_u(&[5.0; 4], &[5.0; 6]), 1.0);
+        assert_eq!(mann_whitney_u(&[], &[1.0, 2.0]), 1.0);
+    }
+
+    /// 20 values between 100 and 104, with outliers of every kind by Tukey's fences, which are
+    /// at 93.25, 97, 107 and 110.75.
+    fn planted_outliers() -> Vec<f64> {
+        let mut values = (0..20).map(|i| f64::from(100 + i % 5)).collect::<Vec<_>>();
+        values.extend([95.0, 109.0, 130.0, 40.0]);
+        values
+    }
+
+    #[test]
+    fn tukey_classifies_planted_outliers() {
+        let outliers = Outliers::classify(&planted_outliers(), OutlierMethod::Tukey);
+        assert_eq!(
+            outliers,
+            Outliers {
+                low_severe: 1,
+                low_mild: 1,
+                high_mild: 1,
+                high_severe: 1,
+            }
+        );
+        assert_eq!(outliers.total(), 4);
+        assert_eq!(outliers.severe(), 2);
+    }
+
+    #[test]
+    fn mad_classifies_planted_outliers() {
+        // The median is 102 and the scaled MAD 2.22, so the fences are at 86.4, 94.2, 109.8
+        // and 117.6, and only the far outliers count.
+        let outliers = Outliers::classify(&planted_outliers(), OutlierMethod::Mad);
+        assert_eq!(
+            outliers,
+            Outliers {
+                low_severe: 1,
+                high_severe: 1,
+                ..Outliers::default()
+            }
+        );
+    }
+
+    #[test]
+    fn too_few_or_identical_values_have_no_outliers() {
+        for method in [OutlierMethod::Tukey, OutlierMethod::Mad] {
+            assert_eq!(Outliers::classify(&[], method), Outliers::default());
+            assert_eq!(Outliers::classify(&[1.0], method), Outliers::default());
+            // Less than 4 values.
+            assert_eq!(
+                Outliers::classify(&[1.0, 1.0, 100.0], method),
+                Outliers::default()
+            );
+            assert_eq!(Outliers::classify(&[7.0; 10], method), Outliers::default());
+        }
+    }
+
+    #[test]
+    fn bootstrap_interval_contains_the_sample_statistics() {
+        let values = (0..25)
+            .map(|i| f64::from(1000 + (i * 37) % 101))
+            .collect::<Vec<_>>();
+        let mut resample = Vec::with_capacity(values.len());
+        let mut statistics = |indices: &[usize]| {
+            resample.clear();
+            resample.extend(indices.iter().map(|&i| values[i]));
+            [mean(&resample), median(&resample)]
+        };
+
+        let [mean_ci, median_ci] = bootstrap(values.len(), &mut statistics).unwrap();
+        assert!(mean_ci.lower < mean(&values) && mean(&values) < mean_ci.upper);
+        assert!(median_ci.lower <= median(&values) && median(&values) <= median_ci.upper);
+        assert!(median_ci.lower < median_ci.upper);
+
+        // The resampling is seeded.
+        assert_eq!(
+            bootstrap(values.len(), &mut statistics),
+            Some([mean_ci, median_ci])
+        );
+    }
+
+    #[test]
+    fn bootstrap_needs_two_samples() {
+        let statistics = |indices: &[usize]| [indices.len() as f64];
+        assert_eq!(bootstrap(0, statistics), None);
+        assert_eq!(bootstrap(1, statistics), None);
+
+        // All resamples of identical samples are identical.
+        let [interval] = bootstrap(2, |_| [5.0]).unwrap();
+        assert_eq!(
+            interval,
+            ConfidenceInterval {
+                lower: 5.0,
+                upper: 5.0
+            }
+        );
+    }
+
+    #[test]
+    fn slice_helpers_handle_empty_input() {
+        assert_eq!(mean(&[]), 0.0);
+        assert_eq!(median(&[]), 0.0);
+        assert_eq!(std_dev(&[1.0]), 0.0);
+        assert_close(std_dev(&[1.0, 3.0]), 2.0f64.sqrt());
+    }
+}
@@ -0,0 +1,84 @@
+use std::{fs, path::Path, process::Command};
+
+use serde::Serialize;
+
+/// Information about the machine and toolchain a benchmark runs on.
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemInfo {
+    pub hostname: Option<String>,
+    pub os: String,
+    pub arch: String,
+    /// CPU model name, from `/proc/cpuinfo` on Linux.
+    pub cpu_model: Option<String>,
+    /// Number of CPUs available to this process.
+    pub cpu_count: usize,
+    /// Total memory in bytes, from `/proc/meminfo` on Linux.
+    pub memory_bytes: Option<u64>,
+    pub rustc_version: Option<String>,
+    /// The version of tokio in `Cargo.lock`.
+    pub tokio_version: Option<String>,
+    pub git_commit: Option<String>,
+}
+
+impl SystemInfo {
+    /// Collects the system information. Every field that can't be determined is left empty.
+    pub fn collect() -> Self {
+        Self {
+            hostname: fs::read_to_string("/etc/hostname")
+                .ok()
+                .or_else(|| command_output("hostname", &[]))
+                .map(|hostname| hostname.trim().to_string()),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            cpu_model: cpu_model(),
+            cpu_count: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            memory_bytes: memory_bytes(),
+            rustc_version: command_output(
+                &std::env::var("RUSTC").unwrap_or("rustc".to_string()),
+                &["--version"],
+            ),
+            tokio_version: locked_version("tokio"),
+            git_commit: command_output("git", &["rev-parse", "HEAD"]),
+        }
+    }
+}
+
+/// Runs `program` in the crate directory and returns its trimmed stdout, if it succeeds.
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program)
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .ok()?;
+
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn cpu_model() -> Option<String> {
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo").ok()?;
+    let line = cpuinfo
+        .lines()
+        .find(|line| line.starts_with("model name"))?;
+    Some(line.split_once(':')?.1.trim().to_string())
+}
+
+fn memory_bytes() -> Option<u64> {
+    let meminfo = fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo.lines().find(|line| line.starts_with("MemTotal:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// Returns the version of `package` in the `Cargo.lock` of this crate.
+fn locked_version(package: &str) -> Option<String> {
+    let lock = fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.lock")).ok()?;
+    let name = format!("name = \"{package}\"");
+
+    let mut lines = lock.lines();
+    lines.find(|line| *line == name)?;
+    let version = lines.next()?.strip_prefix("version = ")?;
+    Some(version.trim_matches('"').to_string())
+}
@@ -0,0 +1,798 @@
+use std::{fmt, time::Duration};
+
+use serde::Serialize;
+use tabled::{
+    Table, Tabled,
+    settings::{Color, Style, object::Rows},
+};
+use tokio_metrics::TaskMetrics;
+
+use super::{
+    AllocationResult, ConfidenceInterval, LatencyResult, LoadResult, OpenLoopResult, Outliers,
+    RuntimeColumns, RuntimeResult, SIGNIFICANCE_LEVEL, ScenarioResult, ThroughputResult,
+    export::{as_nanos, as_optional_nanos},
+    mann_whitney_u, median,
+};
+
+/// The border style of the printed tables.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TableStyle {
+    /// Box drawing characters.
+    #[default]
+    Modern,
+    /// GitHub flavored markdown, to paste into issues and pull requests. Never colored.
+    Markdown,
+    /// ASCII characters only, e.g. for CI logs.
+    Ascii,
+    /// No borders, only aligned columns.
+    None,
+}
+
+impl TableStyle {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "modern" => Ok(Self::Modern),
+            "markdown" => Ok(Self::Markdown),
+            "ascii" => Ok(Self::Ascii),
+            "none" => Ok(Self::None),
+            _ => Err(format!("unknown table style `{value}`")),
+        }
+    }
+
+    /// Renders `table` in this style. Colors, such as the bold best rows, are removed unless
+    /// `color` is set.
+    pub fn render(&self, table: &mut Table, color: bool) -> String {
+        let rendered = match self {
+            Self::Modern => table.with(Style::modern()),
+            Self::Markdown => table.with(Style::markdown()),
+            Self::Ascii => table.with(Style::ascii()),
+            Self::None => table.with(Style::blank()),
+        }
+        .to_string();
+
+        if color && *self != Self::Markdown {
+            rendered
+        } else {
+            strip_ansi(&rendered)
+        }
+    }
+}
+
+impl fmt::Display for TableStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Modern => "modern",
+            Self::Markdown => "markdown",
+            Self::Ascii => "ascii",
+            Self::None => "none",
+        })
+    }
+}
+
+/// Removes ANSI escape sequences, such as colors, from `text`.
+pub(crate) fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip the sequence up to and including its final byte, e.g. `m` in `\x1b[1m`.
+            if chars.next() == Some('[') {
+                chars.find(|c| ('@'..='~').contains(c));
+            }
+            continue;
+        }
+        stripped.push(c);
+    }
+
+    stripped
+}
+
+pub trait ToRow {
+    type Row;
+
+    fn to_row(&self, actor_type: &'static str) -> Self::Row;
+}
+
+impl ToRow for ThroughputResult {
+    type Row = ThroughputRow;
+
+    fn to_row(&self, actor_type: &'static str) -> ThroughputRow {
+        let intervals = self.duration_confidence_intervals();
+        ThroughputRow {
+            actor_type,
+            mean_duration: self.mean_duration(),
+            mean_throughput: self.mean_throughput(),
+            median_duration: self.median_duration(),
+            std_dev_duration: self.std_dev_duration(),
+            cv_duration: self.cv_duration(),
+            mean_duration_ci: intervals.map(|[mean, _]| mean),
+            median_duration_ci: intervals.map(|[_, median]| median),
+            median_throughput: self.median_throughput(),
+            min_duration: self.min_duration(),
+            max_duration: self.max_duration(),
+            min_throughput: self.min_throughput(),
+            max_throughput: self.max_throughput(),
+            max_pending_tasks: self.metrics.max_pending_tasks(),
+            max_outbox_len: self.metrics.max_outbox_len(),
+            outliers: self.outliers,
+            allocations_per_task: self.allocations.allocations_per_task(),
+            bytes_per_task: self.allocations.bytes_per_task(),
+            peak_live_bytes: self.allocations.peak_live_bytes(),
+        }
+    }
+}
+
+impl ToRow for LatencyResult {
+    type Row = LatencyRow;
+    fn to_row(&self, actor_type: &'static str) -> LatencyRow {
+        LatencyRow {
+            actor_type,
+            mean_latency: self.mean_latency(),
+            median_latency: self.quantile(0.5),
+            std_dev_latency: self.std_dev_latency(),
+            cv_latency: self.cv_latency(),
+            mean_latency_ci: self.confidence_intervals.map(|[mean, _, _]| mean),
+            median_latency_ci: self.confidence_intervals.map(|[_, median, _]| median),
+            p99_latency_ci: self.confidence_intervals.map(|[_, _, p99]| p99),
+            min_latency: self.min_latency(),
+            p10_latency: self.quantile(0.1),
+            p90_latency: self.quantile(0.9),
+            p99_latency: self.quantile(0.99),
+            p999_latency: self.quantile(0.999),
+            p9999_latency: self.quantile(0.9999),
+            max_latency: self.max_latency(),
+            outliers: self.outliers,
+        }
+    }
+}
+
+impl ToRow for LoadResult {
+    type Row = LoadRow;
+
+    fn to_row(&self, actor_type: &'static str) -> LoadRow {
+        let metrics = &self.metrics;
+        let steady_state = self.steady_state_metrics();
+        LoadRow {
+            actor_type,
+            total_poll_count: metrics.total_poll_count,
+            total_fast_poll_count: metrics.total_fast_poll_count,
+            total_slow_poll_count: metrics.total_slow_poll_count,
+            mean_idle_duration: metrics.mean_idle_duration(),
+            mean_scheduled_duration: metrics.mean_scheduled_duration(),
+            mean_poll_duration: metrics.mean_poll_duration(),
+            mean_fast_poll_duration: metrics.mean_fast_poll_duration(),
+            mean_slow_poll_duration: metrics.mean_slow_poll_duration(),
+            slow_poll_ratio: metrics.slow_poll_ratio(),
+            load: calculate_load(metrics),
+            outliers: self.outliers,
+            steady_load: steady_state.as_ref().map(calculate_load),
+            steady_mean_poll_duration: steady_state
+                .as_ref()
+                .map(|metrics| metrics.mean_poll_duration()),
+            poll_duration_std_dev: self.poll_duration_std_dev(),
+            steady_slow_poll_ratio: steady_state
+                .as_ref()
+                .map(|metrics| metrics.slow_poll_ratio()),
+            slow_poll_ratio_std_dev: self.slow_poll_ratio_std_dev(),
+            cpu_utilization: self.cpu_utilization(),
+            user_time: self.mean_user_time(),
+            system_time: self.mean_system_time(),
+            peak_rss: self.peak_rss_bytes(),
+            voluntary_context_switches: self.mean_voluntary_context_switches(),
+            involuntary_context_switches: self.mean_involuntary_context_switches(),
+        }
+    }
+}
+
+impl ToRow for RuntimeResult {
+    type Row = RuntimeRow;
+
+    fn to_row(&self, actor_type: &'static str) -> RuntimeRow {
+        RuntimeRow {
+            actor_type,
+            workers: self.workers,
+            busy_ratio: self.busy_ratio(),
+            polls: self.total_polls_count,
+            parks: self.total_park_count,
+            noop_unparks: self.total_noop_count,
+            steals: self.total_steal_count,
+            steal_operations: self.total_steal_operations,
+            remote_schedules: self.num_remote_schedules,
+            local_schedules: self.total_local_schedule_count,
+            overflows: self.total_overflow_count,
+            budget_forced_yields: self.budget_forced_yield_count,
+            max_global_queue_depth: self.max_global_queue_depth,
+            max_local_queue_depth: self.max_local_queue_depth,
+        }
+    }
+}
+
+impl ToRow for AllocationResult {
+    type Row = AllocationRow;
+
+    fn to_row(&self, actor_type: &'static str) -> AllocationRow {
+        AllocationRow {
+            actor_type,
+            allocations: self.stats.allocations,
+            deallocations: self.stats.deallocations,
+            allocated_bytes: self.stats.allocated_bytes,
+            peak_live_bytes: self.stats.peak_live_bytes,
+            allocations_per_task: self.stats.allocations as f64 / self.tasks as f64,
+            bytes_per_task: self.stats.allocated_bytes as f64 / self.tasks as f64,
+        }
+    }
+}
+
+impl ToRow for OpenLoopResult {
+    type Row = Vec<OpenLoopRow>;
+
+    fn to_row(&self, actor_type: &'static str) -> Vec<OpenLoopRow> {
+        self.points
+            .iter()
+            .map(|point| OpenLoopRow {
+                actor_type,
+                offered_rate: point.offered_rate,
+                achieved_throughput: point.achieved_throughput,
+                mean_latency: point.latency.mean(),
+                median_latency: point.latency.quantile(0.5),
+                p99_latency: point.latency.quantile(0.99),
+                p999_latency: point.latency.quantile(0.999),
+                max_latency: point.latency.max(),
+                max_send_delay: point.max_send_delay,
+            })
+            .collect()
+    }
+}
+
+/// How a row of a comparison table compares to the best row.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VsBest {
+    Best,
+    /// Not significantly different from the best row, with the p-value of the Mann-Whitney U
+    /// test.
+    Tied(f64),
+    /// Significantly worse than the best row, with the p-value of the Mann-Whitney U test.
+    Worse(f64),
+    /// There are not enough samples to test for significance.
+    Unknown,
+}
+
+impl VsBest {
+    /// Compares the `samples` of a row to the samples of the best row, where lower samples are
+    /// better.
+    pub fn compare(best: &[f64], samples: &[f64]) -> Self {
+        if best.len() < 2 || samples.len() < 2 {
+            return VsBest::Unknown;
+        }
+
+        let p_value = mann_whitney_u(best, samples);
+        // The test is two-sided, so a significant difference only makes the row worse if its
+        // samples are higher.
+        if p_value < SIGNIFICANCE_LEVEL && median(samples) > median(best) {
+            VsBest::Worse(p_value)
+        } else {
+            VsBest::Tied(p_value)
+        }
+    }
+}
+
+impl fmt::Display for VsBest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VsBest::Best => f.write_str("best"),
+            VsBest::Tied(p_value) => write!(f, "tied (p={p_value:.3})"),
+            VsBest::Worse(p_value) if *p_value < 0.001 => f.write_str("worse (p<0.001)"),
+            VsBest::Worse(p_value) => write!(f, "worse (p={p_value:.3})"),
+            VsBest::Unknown => f.write_str("-"),
+        }
+    }
+}
+
+#[derive(Debug, Tabled, Clone)]
+struct VsBestColumn {
+    vs_best: VsBest,
+}
+
+/// Builds a table that compares rows, given the per-iteration samples of every row (see
+/// [`ScenarioResult::samples`](super::ScenarioResult::samples)), where lower samples are better.
+///
+/// The rows are sorted by the median of their samples, from best to worst, and rows without
+/// samples come last. Every row is compared to the best row with the Mann-Whitney U test, and
+/// the result is shown in a `vs_best` column. The best row and every row that is not
+/// significantly worse are bold.
+pub fn comparison_table<R: Tabled>(mut rows: Vec<(RuntimeColumns, R, Vec<f64>)>) -> Table {
+    rows.sort_by(|(_, _, a), (_, _, b)| {
+        a.is_empty()
+            .cmp(&b.is_empty())
+            .then_with(|| median(a).total_cmp(&median(b)))
+    });
+
+    let best = rows.first().map(|(_, _, samples)| samples.clone());
+    let mut bold = Vec::new();
+
+    let rows = rows
+        .into_iter()
+        .enumerate()
+        .map(|(i, (columns, row, samples))| {
+            let vs_best = match &best {
+                _ if i == 0 => VsBest::Best,
+                Some(best) => VsBest::compare(best, &samples),
+                None => VsBest::Unknown,
+            };
+            if matches!(vs_best, VsBest::Best | VsBest::Tied(_)) {
+                bold.push(i + 1);
+            }
+
+            (columns, row, VsBestColumn { vs_best })
+        })
+        .collect::<Vec<_>>();
+
+    let mut table = Table::new(rows);
+    for row in bold {
+        table.modify(Rows::one(row), Color::BOLD);
+    }
+    table
+}
+
+pub fn format_duration(duration: &Duration) -> String {
+    format!("{:.2?}", duration)
+}
+
+pub fn format_throughput(throughput: &f64) -> String {
+    if *throughput > 1_000_000.0 {
+        format!("{:.3}M", throughput / 1_000_000.0)
+    } else if *throughput > 1_000.0 {
+        format!("{:.3}k", throughput / 1000.0)
+    } else {
+        format!("{:.3}", throughput)
+    }
+}
+
+pub fn format_ratio(ratio: &f64) -> String {
+    format!("{:.2}", ratio)
+}
+
+pub fn format_bytes(bytes: &u64) -> String {
+    let bytes = *bytes as f64;
+    if bytes >= 1024.0 * 1024.0 {
+        format!("{:.2}MiB", bytes / (1024.0 * 1024.0))
+    } else if bytes >= 1024.0 {
+        format!("{:.2}KiB", bytes / 1024.0)
+    } else {
+        format!("{bytes}B")
+    }
+}
+
+fn format_optional_ratio(ratio: &Option<f64>) -> String {
+    ratio.as_ref().map_or("-".to_string(), format_ratio)
+}
+
+fn format_optional_throughput(throughput: &Option<f64>) -> String {
+    throughput
+        .as_ref()
+        .map_or("-".to_string(), format_throughput)
+}
+
+fn format_optional_bytes(bytes: &Option<u64>) -> String {
+    bytes.as_ref().map_or("-".to_string(), format_bytes)
+}
+
+fn format_optional_duration(duration: &Option<Duration>) -> String {
+    duration.as_ref().map_or("-".to_string(), format_duration)
+}
+
+fn format_optional_percentage(percentage: &Option<f64>) -> String {
+    percentage
+        .as_ref()
+        .map_or("-".to_string(), format_percentage)
+}
+
+fn format_optional_count(count: &Option<u64>) -> String {
+    count.map_or("-".to_string(), |count| count.to_string())
+}
+
+/// Formats a confidence interval of durations in nanoseconds, e.g. `[1.20ms, 1.31ms]`.
+pub fn format_duration_ci(interval: &Option<ConfidenceInterval>) -> String {
+    interval.map_or("-".to_string(), |interval| {
+        format!(
+            "[{}, {}]",
+            format_duration(&Duration::from_nanos(interval.lower as u64)),
+            format_duration(&Duration::from_nanos(interval.upper as u64))
+        )
+    })
+}
+
+/// Formats outliers as their total, e.g. `3 (1 severe)`.
+pub fn format_outliers(outliers: &Outliers) -> String {
+    match (outliers.total(), outliers.severe()) {
+        (0, _) => "0".to_string(),
+        (total, 0) => total.to_string(),
+        (total, severe) => format!("{total} ({severe} severe)"),
+    }
+}
+
+pub fn format_percentage(percentage: &f64) -> String {
+    format!("{:.2}%", percentage * 100.0)
+}
+
+#[derive(Debug, Tabled, Clone, Serialize)]
+pub struct ThroughputRow {
+    /// Name of the actor.
+    pub actor_type: &'static str,
+    /// Mean duration.
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub mean_duration: Duration,
+    /// Mean throughput.
+    #[tabled(display = "format_throughput")]
+    pub mean_throughput: f64,
+    /// Median duration.
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub median_duration: Duration,
+    /// Median throughput.
+    #[tabled(display = "format_throughput")]
+    pub median_throughput: f64,
+    /// Standard deviation of the duration.
+    #[tabled(rename = "duration_stddev", display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub std_dev_duration: Duration,
+    /// Coefficient of variation of the duration.
+    #[tabled(rename = "duration_cv", display = "format_percentage")]
+    pub cv_duration: f64,
+    /// Confidence interval of the mean duration, in nanoseconds.
+    #[tabled(display = "format_duration_ci")]
+    pub mean_duration_ci: Option<ConfidenceInterval>,
+    /// Confidence interval of the median duration, in nanoseconds.
+    #[tabled(display = "format_duration_ci")]
+    pub median_duration_ci: Option<ConfidenceInterval>,
+    /// Min duration.
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub min_duration: Duration,
+    /// Max duration.
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub max_duration: Duration,
+    /// Min throughput.
+    #[tabled(display = "format_throughput")]
+    pub min_throughput: f64,
+    /// Max throughput.
+    #[tabled(display = "format_throughput")]
+    pub max_throughput: f64,
+    /// Max pending tasks.
+    pub max_pending_tasks: usize,
+    /// Max finished results that waited for room in the results channel.
+    pub max_outbox_len: usize,
+    /// Outliers among the iteration durations.
+    #[tabled(display = "format_outliers")]
+    pub outliers: Outliers,
+    /// Allocations per processed task, if allocations are tracked.
+    #[tabled(display = "format_optional_ratio")]
+    pub allocations_per_task: Option<f64>,
+    /// Bytes allocated per processed task, if allocations are tracked.
+    #[tabled(display = "format_optional_ratio")]
+    pub bytes_per_task: Option<f64>,
+    /// Highest number of bytes live at once above the start of the run, if allocations are
+    /// tracked.
+    #[tabled(display = "format_optional_bytes")]
+    pub peak_live_bytes: Option<u64>,
+}
+
+#[derive(Debug, Tabled, Clone, Serialize)]
+pub struct LatencyRow {
+    /// Name of the actor.
+    pub actor_type: &'static str,
+    /// Mean latency.
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub mean_latency: Duration,
+    /// Median latency.
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub median_latency: Duration,
+    /// Standard deviation of the latency.
+    #[tabled(rename = "latency_stddev", display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub std_dev_latency: Duration,
+    /// Coefficient of variation of the latency.
+    #[tabled(rename = "latency_cv", display = "format_percentage")]
+    pub cv_latency: f64,
+    /// Confidence interval of the mean latency, in nanoseconds.
+    #[tabled(display = "format_duration_ci")]
+    pub mean_latency_ci: Option<ConfidenceInterval>,
+    /// Confidence interval of the median latency, in nanoseconds.
+    #[tabled(display = "format_duration_ci")]
+    pub median_latency_ci: Option<ConfidenceInterval>,
+    /// Confidence interval of the 99th percentile latency, in nanoseconds.
+    #[tabled(display = "format_duration_ci")]
+    pub p99_latency_ci: Option<ConfidenceInterval>,
+    /// Min latency.
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub min_latency: Duration,
+    /// Max latency.
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub max_latency: Duration,
+    /// 10th percentile latency.
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub p10_latency: Duration,
+    /// 90th percentile latency.
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub p90_latency: Duration,
+    /// 99th percentile latency.
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub p99_latency: Duration,
+    /// 99.9th percentile latency.
+    #[tabled(display = "format_duration", rename = "p99.9_latency")]
+    #[serde(serialize_with = "as_nanos")]
+    pub p999_latency: Duration,
+    /// 99.99th percentile latency.
+    #[tabled(display = "format_duration", rename = "p99.99_latency")]
+    #[serde(serialize_with = "as_nanos")]
+    pub p9999_latency: Duration,
+    /// Outliers among the median latencies of the iterations.
+    #[tabled(display = "format_outliers")]
+    pub outliers: Outliers,
+}
+
+#[derive(Debug, Tabled, Clone, Serialize)]
+pub struct AllocationRow {
+    /// Name of the actor.
+    pub actor_type: &'static str,
+    /// Total number of allocations.
+    pub allocations: u64,
+    /// Total number of deallocations.
+    pub deallocations: u64,
+    /// Total number of bytes allocated.
+    pub allocated_bytes: u64,
+    /// Highest number of bytes live at once above the start of the run.
+    #[tabled(display = "format_bytes")]
+    pub peak_live_bytes: u64,
+    /// Allocations per processed task.
+    #[tabled(display = "format_ratio")]
+    pub allocations_per_task: f64,
+    /// Bytes allocated per processed task.
+    #[tabled(display = "format_ratio")]
+    pub bytes_per_task: f64,
+}
+
+#[derive(Debug, Tabled, Clone, Serialize)]
+pub struct LoadRow {
+    pub actor_type: &'static str,
+    #[tabled(display = "format_percentage")]
+    pub load: f64,
+    pub total_poll_count: u64,
+    pub total_fast_poll_count: u64,
+    pub total_slow_poll_count: u64,
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub mean_idle_duration: Duration,
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub mean_scheduled_duration: Duration,
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub mean_poll_duration: Duration,
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub mean_fast_poll_duration: Duration,
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub mean_slow_poll_duration: Duration,
+    #[tabled(display = "format_percentage")]
+    pub slow_poll_ratio: f64,
+    /// Outliers among the mean poll durations of the iterations.
+    #[tabled(display = "format_outliers")]
+    pub outliers: Outliers,
+    /// Load over the iterations after the warm-up.
+    #[tabled(display = "format_optional_percentage")]
+    pub steady_load: Option<f64>,
+    /// Mean poll duration over the iterations after the warm-up.
+    #[tabled(display = "format_optional_duration")]
+    #[serde(serialize_with = "as_optional_nanos")]
+    pub steady_mean_poll_duration: Option<Duration>,
+    /// Standard deviation of the mean poll duration of the iterations after the warm-up.
+    #[tabled(rename = "poll_duration_stddev", display = "format_optional_duration")]
+    #[serde(serialize_with = "as_optional_nanos")]
+    pub poll_duration_std_dev: Option<Duration>,
+    /// Slow poll ratio over the iterations after the warm-up.
+    #[tabled(display = "format_optional_percentage")]
+    pub steady_slow_poll_ratio: Option<f64>,
+    /// Standard deviation of the slow poll ratio of the iterations after the warm-up.
+    #[tabled(
+        rename = "slow_poll_ratio_stddev",
+        display = "format_optional_percentage"
+    )]
+    pub slow_poll_ratio_std_dev: Option<f64>,
+    /// CPU time of the threads of the process as a fraction of the wall-clock time, if it can be
+    /// sampled.
+    #[tabled(rename = "cpu", display = "format_optional_percentage")]
+    pub cpu_utilization: Option<f64>,
+    /// Mean user CPU time of the threads of the process per iteration.
+    #[tabled(display = "format_optional_duration")]
+    #[serde(serialize_with = "as_optional_nanos")]
+    pub user_time: Option<Duration>,
+    /// Mean system CPU time of the process per iteration.
+    #[tabled(display = "format_optional_duration")]
+    #[serde(serialize_with = "as_optional_nanos")]
+    pub system_time: Option<Duration>,
+    /// Highest resident set size of the process.
+    #[tabled(display = "format_optional_bytes")]
+    pub peak_rss: Option<u64>,
+    /// Mean voluntary context switches of the threads of the process per iteration.
+    #[tabled(rename = "voluntary_switches", display = "format_optional_count")]
+    pub voluntary_context_switches: Option<u64>,
+    /// Mean involuntary context switches of the threads of the process per iteration.
+    #[tabled(rename = "involuntary_switches", display = "format_optional_count")]
+    pub involuntary_context_switches: Option<u64>,
+}
+
+#[derive(Debug, Tabled, Clone, Serialize)]
+pub struct RuntimeRow {
+    /// Name of the actor.
+    pub actor_type: &'static str,
+    /// Number of worker threads.
+    pub workers: usize,
+    /// Fraction of time the workers were busy.
+    #[tabled(display = "format_percentage")]
+    pub busy_ratio: f64,
+    /// Number of task polls.
+    pub polls: u64,
+    /// Number of times a worker parked.
+    pub parks: u64,
+    /// Number of times a worker unparked without finding new work.
+    pub noop_unparks: u64,
+    /// Number of stolen tasks.
+    pub steals: u64,
+    /// Number of steal operations.
+    pub steal_operations: u64,
+    /// Number of tasks scheduled from outside of the runtime.
+    pub remote_schedules: u64,
+    /// Number of tasks scheduled on a local queue.
+    pub local_schedules: u64,
+    /// Number of local queue overflows.
+    pub overflows: u64,
+    /// Number of yields forced by the cooperative budget.
+    pub budget_forced_yields: u64,
+    /// Largest global queue depth seen while sampling.
+    pub max_global_queue_depth: usize,
+    /// Largest local queue depth seen while sampling.
+    pub max_local_queue_depth: usize,
+}
+
+#[derive(Debug, Tabled, Clone, Serialize)]
+pub struct OpenLoopRow {
+    /// Name of the actor.
+    pub actor_type: &'static str,
+    /// Offered load in tasks per second.
+    #[tabled(display = "format_throughput")]
+    pub offered_rate: f64,
+    /// Completed tasks per second.
+    #[tabled(display = "format_throughput")]
+    pub achieved_throughput: f64,
+    /// Mean latency from the intended send time.
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub mean_latency: Duration,
+    /// Median latency from the intended send time.
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub median_latency: Duration,
+    /// 99th percentile latency from the intended send time.
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub p99_latency: Duration,
+    /// 99.9th percentile latency from the intended send time.
+    #[tabled(display = "format_duration", rename = "p99.9_latency")]
+    #[serde(serialize_with = "as_nanos")]
+    pub p999_latency: Duration,
+    /// Max latency from the intended send time.
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub max_latency: Duration,
+    /// Largest delay of a send after its intended send time.
+    #[tabled(display = "format_duration")]
+    #[serde(serialize_with = "as_nanos")]
+    pub max_send_delay: Duration,
+}
+
+/// The headline numbers of all scenarios of an actor on one row. Scenarios that weren't run are
+/// shown as `-`.
+#[derive(Debug, Tabled, Clone, Serialize)]
+pub struct SummaryRow {
+    pub actor_type: &'static str,
+    /// Mean throughput in tasks per second.
+    #[tabled(display = "format_optional_throughput")]
+    pub throughput: Option<f64>,
+    #[tabled(display = "format_optional_duration")]
+    #[serde(serialize_with = "as_optional_nanos")]
+    pub median_latency: Option<Duration>,
+    #[tabled(display = "format_optional_duration")]
+    #[serde(serialize_with = "as_optional_nanos")]
+    pub p99_latency: Option<Duration>,
+    /// Load over the iterations after the warm-up.
+    #[tabled(display = "format_optional_percentage")]
+    pub load: Option<f64>,
+}
+
+impl SummaryRow {
+    pub fn new(actor_type: &'static str) -> Self {
+        Self {
+            actor_type,
+            throughput: None,
+            median_latency: None,
+            p99_latency: None,
+            load: None,
+        }
+    }
+
+    /// Fills in the headline numbers of `result`.
+    pub fn add(&mut self, result: &ScenarioResult) {
+        match result {
+            ScenarioResult::Throughput(result) => self.throughput = Some(result.mean_throughput()),
+            ScenarioResult::Latency(result) => {
+                self.median_latency = Some(result.quantile(0.5));
+                self.p99_latency = Some(result.quantile(0.99));
+            }
+            ScenarioResult::Load(result) => {
+                self.load = result.steady_state_metrics().as_ref().map(calculate_load);
+            }
+            // The open-loop scenario has no single headline number.
+            ScenarioResult::OpenLoop(_) | ScenarioResult::SlowConsumer(_) => {}
+        }
+    }
+}
+
+pub(crate) fn calculate_load(metrics: &TaskMetrics) -> f64 {
+    // Calculate load as percentage of time spent actively working vs total runtime
+    let total_work_time = metrics.total_poll_duration;
+    let total_runtime = metrics.total_poll_duration
+        + metrics.total_idle_duration
+        + metrics.total_scheduled_duration;
+
+    if total_runtime.as_nanos() == 0 {
+        return 0.0;
+    }
+
+    // Load percentage: 100% = fully utilized, 0% = completely idle
+    total_work_time.as_secs_f64() / total_runtime.as_secs_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_samples_are_tied() {
+        let samples = [3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0];
+        assert!(matches!(
+            VsBest::compare(&samples, &samples),
+            VsBest::Tied(p_value) if p_value > 0.9
+        ));
+    }
+
+    #[test]
+    fn only_significantly_higher_samples_are_worse() {
+        let best = (0..10).map(f64::from).collect::<Vec<_>>();
+        let higher = (10..20).map(f64::from).collect::<Vec<_>>();
+        assert!(matches!(VsBest::compare(&best, &higher), VsBest::Worse(_)));
+
+        // Rows are sorted by their median, but a row with lower samples than the best row
+        // must not be marked as worse.
+        assert!(matches!(VsBest::compare(&higher, &best), VsBest::Tied(_)));
+    }
+
+    #[test]
+    fn single_samples_are_unknown() {
+        assert_eq!(VsBest::compare(&[1.0], &[2.0, 3.0]), VsBest::Unknown);
+        assert_eq!(VsBest::compare(&[1.0, 2.0], &[]), VsBest::Unknown);
+    }
+}
@@ -1,2 +1,10 @@
 #[path = "01/mod.rs"]
 pub mod _01;
+#[path = "02/mod.rs"]
+pub mod _02;
+#[path = "03/mod.rs"]
+pub mod _03;
+#[path = "04/mod.rs"]
+pub mod _04;
+
+pub mod harness;